@@ -0,0 +1,248 @@
+use std::collections::HashSet;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Metric {
+    Manhattan,
+    Chebyshev,
+}
+
+impl Metric {
+    pub fn distance(&self, a: (i64, i64), b: (i64, i64)) -> i64 {
+        let dx = (a.0 - b.0).abs();
+        let dy = (a.1 - b.1).abs();
+
+        match self {
+            Metric::Manhattan => dx + dy,
+            Metric::Chebyshev => std::cmp::max(dx, dy),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Owner {
+    /// Index of the single nearest seed.
+    Seed(usize),
+    /// Two or more seeds are equally near.
+    Tie,
+}
+
+/// The nearest seed for every cell in an area around a set of seeds, big enough to hold every
+/// finite region whole.
+#[derive(Debug)]
+pub struct Partition {
+    /// The corners of the partitioned area.
+    pub min: (i64, i64),
+    pub max: (i64, i64),
+    seed_count: usize,
+    owners: Vec<Owner>,
+}
+
+fn bounding_box(seeds: &[(i64, i64)]) -> ((i64, i64), (i64, i64)) {
+    let min_x = seeds.iter().map(|seed| seed.0).min().unwrap_or(0);
+    let max_x = seeds.iter().map(|seed| seed.0).max().unwrap_or(-1);
+    let min_y = seeds.iter().map(|seed| seed.1).min().unwrap_or(0);
+    let max_y = seeds.iter().map(|seed| seed.1).max().unwrap_or(-1);
+
+    ((min_x, min_y), (max_x, max_y))
+}
+
+fn nearest(seeds: &[(i64, i64)], metric: Metric, point: (i64, i64)) -> Owner {
+    let mut owner = Owner::Tie;
+    let mut best = i64::MAX;
+
+    for (i, seed) in seeds.iter().enumerate() {
+        let distance = metric.distance(*seed, point);
+        if distance < best {
+            best = distance;
+            owner = Owner::Seed(i);
+        } else if distance == best {
+            owner = Owner::Tie;
+        }
+    }
+
+    owner
+}
+
+impl Partition {
+    /// Partitions the bounding box of `seeds`. Under Chebyshev distance a finite region can
+    /// bulge out of the box, so the box is grown by its width plus height, past which every
+    /// cell has the same owner as the next cell out.
+    pub fn new(seeds: &[(i64, i64)], metric: Metric) -> Self {
+        let (min, max) = bounding_box(seeds);
+        let margin = match metric {
+            Metric::Manhattan => 0,
+            Metric::Chebyshev => (max.0 - min.0) + (max.1 - min.1) + 1,
+        };
+        let min = (min.0 - margin, min.1 - margin);
+        let max = (max.0 + margin, max.1 + margin);
+
+        let mut owners = Vec::new();
+        for y in min.1..=max.1 {
+            for x in min.0..=max.0 {
+                owners.push(nearest(seeds, metric, (x, y)));
+            }
+        }
+
+        Partition {
+            min,
+            max,
+            seed_count: seeds.len(),
+            owners,
+        }
+    }
+
+    fn width(&self) -> i64 {
+        self.max.0 - self.min.0 + 1
+    }
+
+    pub fn owner(&self, x: i64, y: i64) -> Option<Owner> {
+        if x < self.min.0 || x > self.max.0 || y < self.min.1 || y > self.max.1 {
+            return None;
+        }
+
+        let index = (y - self.min.1) * self.width() + (x - self.min.0);
+        self.owners.get(index as usize).copied()
+    }
+
+    /// The number of cells in the partitioned area owned by each seed.
+    pub fn areas(&self) -> Vec<usize> {
+        let mut areas = vec![0; self.seed_count];
+        for owner in self.owners.iter() {
+            if let Owner::Seed(i) = owner {
+                areas[*i] += 1;
+            }
+        }
+
+        areas
+    }
+
+    /// Seeds whose regions extend forever. Outside the partitioned area each cell has the same
+    /// owner as its neighbor towards the area, so these are the owners of its edge.
+    pub fn infinite(&self) -> HashSet<usize> {
+        let mut infinite = HashSet::new();
+
+        let mut edge = Vec::new();
+        for x in self.min.0..=self.max.0 {
+            edge.push((x, self.min.1));
+            edge.push((x, self.max.1));
+        }
+        for y in self.min.1..=self.max.1 {
+            edge.push((self.min.0, y));
+            edge.push((self.max.0, y));
+        }
+
+        for (x, y) in edge {
+            if let Some(Owner::Seed(i)) = self.owner(x, y) {
+                infinite.insert(i);
+            }
+        }
+
+        infinite
+    }
+
+    pub fn largest_finite_area(&self) -> Option<usize> {
+        let infinite = self.infinite();
+
+        self.areas()
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| !infinite.contains(i))
+            .map(|(_, area)| area)
+            .max()
+    }
+}
+
+/// Sums of distances from each coordinate in `range` to every value in `values`.
+fn axis_totals(values: &[i64], range: std::ops::RangeInclusive<i64>) -> Vec<i64> {
+    range
+        .map(|coordinate| values.iter().map(|value| (value - coordinate).abs()).sum())
+        .collect()
+}
+
+/// Counts the cells, anywhere on the plane, whose distances to all seeds sum to less than
+/// `limit`.
+pub fn count_within_total_distance(seeds: &[(i64, i64)], metric: Metric, limit: i64) -> usize {
+    if seeds.is_empty() {
+        return 0;
+    }
+
+    // Every step outside the bounding box moves away from all seeds at once, so no cell
+    // further out than this can qualify.
+    let margin = limit / seeds.len() as i64 + 1;
+    let (min, max) = bounding_box(seeds);
+    let x_range = min.0 - margin..=max.0 + margin;
+    let y_range = min.1 - margin..=max.1 + margin;
+
+    match metric {
+        Metric::Manhattan => {
+            // Manhattan totals split into independent x and y sums.
+            let xs: Vec<i64> = seeds.iter().map(|seed| seed.0).collect();
+            let ys: Vec<i64> = seeds.iter().map(|seed| seed.1).collect();
+            let x_totals = axis_totals(&xs, x_range);
+            let mut y_totals = axis_totals(&ys, y_range);
+            y_totals.sort_unstable();
+
+            x_totals
+                .into_iter()
+                .map(|x_total| y_totals.partition_point(|y_total| x_total + y_total < limit))
+                .sum()
+        }
+        Metric::Chebyshev => {
+            let mut count = 0;
+            for y in y_range {
+                for x in x_range.clone() {
+                    let total: i64 = seeds
+                        .iter()
+                        .map(|seed| metric.distance(*seed, (x, y)))
+                        .sum();
+                    if total < limit {
+                        count += 1;
+                    }
+                }
+            }
+
+            count
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seeds() -> Vec<(i64, i64)> {
+        vec![(1, 1), (1, 6), (8, 3), (3, 4), (5, 5), (8, 9)]
+    }
+
+    #[test]
+    fn test_largest_finite_area() {
+        let partition = Partition::new(&seeds(), Metric::Manhattan);
+
+        assert_eq!(partition.owner(0, 4), None);
+        assert_eq!(partition.owner(1, 4), Some(Owner::Tie));
+        assert_eq!(partition.largest_finite_area(), Some(17));
+    }
+
+    #[test]
+    fn test_chebyshev_infinite() {
+        // The middle seed owns (2, -1), (2, 0) and (2, 1): a finite region that reaches past
+        // the edge of the one-row bounding box.
+        let partition = Partition::new(&[(0, 0), (4, 0), (2, 0)], Metric::Chebyshev);
+
+        assert_eq!(partition.owner(2, 1), Some(Owner::Seed(2)));
+        assert_eq!(partition.owner(2, 2), Some(Owner::Tie));
+        assert_eq!(partition.infinite(), [0, 1].iter().copied().collect());
+        assert_eq!(partition.largest_finite_area(), Some(3));
+
+        let partition = Partition::new(&seeds(), Metric::Chebyshev);
+        assert_eq!(partition.infinite(), [0, 1, 2, 5].iter().copied().collect());
+    }
+
+    #[test]
+    fn test_count_within_total_distance() {
+        assert_eq!(
+            count_within_total_distance(&seeds(), Metric::Manhattan, 32),
+            16
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn cross(a: &Direction, b: &Direction) -> i64 {
+    a.dx * b.dy - a.dy * b.dx
+}
+
+fn dot(a: &Direction, b: &Direction) -> i64 {
+    a.dx * b.dx + a.dy * b.dy
+}
+
+/// An exact heading between grid points: the offset divided by its gcd. The y axis points down,
+/// as it does on the map.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Direction {
+    pub dx: i64,
+    pub dy: i64,
+}
+
+impl Direction {
+    pub const UP: Direction = Direction { dx: 0, dy: -1 };
+
+    /// None for the zero vector, which has no heading.
+    pub fn new(dx: i64, dy: i64) -> Option<Direction> {
+        let divisor = gcd(dx, dy);
+        if divisor == 0 {
+            return None;
+        }
+
+        Some(Direction {
+            dx: dx / divisor,
+            dy: dy / divisor,
+        })
+    }
+
+    pub fn between(from: (i64, i64), to: (i64, i64)) -> Option<Direction> {
+        Direction::new(to.0 - from.0, to.1 - from.1)
+    }
+
+    /// 0 if this heading is less than half a turn clockwise from `start`, otherwise 1.
+    fn half(&self, start: &Direction) -> u8 {
+        match cross(start, self).cmp(&0) {
+            Ordering::Greater => 0,
+            Ordering::Less => 1,
+            Ordering::Equal if dot(start, self) > 0 => 0,
+            Ordering::Equal => 1,
+        }
+    }
+
+    /// Orders headings by how far clockwise they are from `start`, which itself comes first.
+    pub fn clockwise_cmp(&self, other: &Direction, start: &Direction) -> Ordering {
+        self.half(start)
+            .cmp(&other.half(start))
+            .then_with(|| 0.cmp(&cross(self, other)))
+    }
+}
+
+/// Every target seen from `origin`, grouped by heading and sorted nearest first within each.
+fn lines_of_sight(
+    origin: (i64, i64),
+    targets: &[(i64, i64)],
+) -> HashMap<Direction, Vec<(i64, i64)>> {
+    let mut lines: HashMap<Direction, Vec<(i64, i64)>> = HashMap::new();
+    for target in targets.iter() {
+        if let Some(direction) = Direction::between(origin, *target) {
+            lines.entry(direction).or_default().push(*target);
+        }
+    }
+
+    for line in lines.values_mut() {
+        line.sort_by_key(|(x, y)| (x - origin.0).abs() + (y - origin.1).abs());
+    }
+
+    lines
+}
+
+/// The targets with nothing else in the way, in clockwise order starting straight up.
+pub fn visible(origin: (i64, i64), targets: &[(i64, i64)]) -> Vec<(i64, i64)> {
+    let mut visible: Vec<(Direction, (i64, i64))> = lines_of_sight(origin, targets)
+        .into_iter()
+        .map(|(direction, line)| (direction, line[0]))
+        .collect();
+    visible.sort_by(|a, b| a.0.clockwise_cmp(&b.0, &Direction::UP));
+
+    visible.into_iter().map(|(_, target)| target).collect()
+}
+
+/// The order a laser sweeping clockwise from `start` destroys every target, one per heading per
+/// rotation.
+pub fn vaporisation_order(
+    origin: (i64, i64),
+    targets: &[(i64, i64)],
+    start: &Direction,
+) -> Vec<(i64, i64)> {
+    let mut order: Vec<(usize, Direction, (i64, i64))> = Vec::new();
+    for (direction, line) in lines_of_sight(origin, targets) {
+        for (rotation, target) in line.into_iter().enumerate() {
+            order.push((rotation, direction, target));
+        }
+    }
+
+    order.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.clockwise_cmp(&b.1, start)));
+
+    order.into_iter().map(|(_, _, target)| target).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clockwise_order() {
+        let mut directions: Vec<Direction> = [(-1, -1), (1, 0), (0, 1), (0, -1), (-1, 0), (2, -1)]
+            .iter()
+            .filter_map(|(dx, dy)| Direction::new(*dx, *dy))
+            .collect();
+        directions.sort_by(|a, b| a.clockwise_cmp(b, &Direction::UP));
+
+        let offsets: Vec<(i64, i64)> = directions.iter().map(|d| (d.dx, d.dy)).collect();
+        assert_eq!(
+            offsets,
+            vec![(0, -1), (2, -1), (1, 0), (0, 1), (-1, 0), (-1, -1)]
+        );
+    }
+
+    #[test]
+    fn test_vaporisation_order() {
+        let map = [
+            ".#....#####...#..",
+            "##...##.#####..##",
+            "##...#...#.#####.",
+            "..#.....#...###..",
+            "..#.#.....#....##",
+        ];
+        let mut asteroids = Vec::new();
+        for (y, row) in map.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                if c == '#' {
+                    asteroids.push((x as i64, y as i64));
+                }
+            }
+        }
+
+        let order = vaporisation_order((8, 3), &asteroids, &Direction::UP);
+        assert_eq!(
+            order[..9].to_vec(),
+            vec![
+                (8, 1),
+                (9, 0),
+                (9, 1),
+                (10, 0),
+                (9, 2),
+                (11, 1),
+                (12, 1),
+                (11, 2),
+                (15, 1)
+            ]
+        );
+        assert_eq!(order.last(), Some(&(14, 3)));
+        assert_eq!(visible((8, 3), &asteroids).len(), 30);
+    }
+}
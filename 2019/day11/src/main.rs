@@ -1,6 +1,6 @@
 mod intcode;
-mod map;
 mod point;
+mod sparse_grid;
 
 use std::collections::VecDeque;
 use std::str::FromStr;
@@ -9,8 +9,10 @@ use anyhow::{format_err, Result};
 use log::{debug, info, trace};
 
 use crate::intcode::Program;
-use crate::map::Map;
 use crate::point::Point;
+use crate::sparse_grid::SparseGrid;
+
+type Map = SparseGrid<char>;
 
 enum Direction {
     Up,
@@ -67,7 +69,7 @@ impl Robot {
     }
 
     fn color_to_input(&self, map: &Map) -> Result<i64> {
-        let color = map.get(&self.position).unwrap();
+        let color = map.get(self.position.x as i64, self.position.y as i64);
         match color {
             // '*' for painted black, '.' for not yet painted
             '.' | '*' => Ok(0),
@@ -82,8 +84,9 @@ impl Robot {
 
         match self.brain.run_to_next_output(&mut inputs)? {
             Some(color) => {
-                map.set_to_char(
-                    &self.position,
+                map.set(
+                    self.position.x as i64,
+                    self.position.y as i64,
                     match color {
                         0 => '*',
                         1 => '#',
@@ -115,27 +118,20 @@ impl Robot {
 }
 
 fn count_painted_points(map: &Map) -> u64 {
-    let mut total = 0;
-    for row in map.data.values() {
-        for ch in row.values() {
-            if *ch == '*' || *ch == '#' {
-                total += 1;
-            }
-        }
-    }
-
-    total
+    map.iter()
+        .filter(|(_, ch)| **ch == '*' || **ch == '#')
+        .count() as u64
 }
 
 fn main() -> Result<()> {
     env_logger::from_env(env_logger::Env::default().default_filter_or("info")).init();
 
     let brain = Program::from_str("3,8,1005,8,318,1106,0,11,0,0,0,104,1,104,0,3,8,1002,8,-1,10,1001,10,1,10,4,10,108,1,8,10,4,10,1002,8,1,28,1,107,14,10,1,107,18,10,3,8,102,-1,8,10,101,1,10,10,4,10,108,1,8,10,4,10,102,1,8,58,1006,0,90,2,1006,20,10,3,8,1002,8,-1,10,101,1,10,10,4,10,1008,8,1,10,4,10,1001,8,0,88,2,103,2,10,2,4,7,10,3,8,1002,8,-1,10,101,1,10,10,4,10,1008,8,1,10,4,10,1001,8,0,118,1,1009,14,10,1,1103,9,10,3,8,1002,8,-1,10,1001,10,1,10,4,10,108,0,8,10,4,10,1002,8,1,147,1006,0,59,1,104,4,10,2,106,18,10,3,8,102,-1,8,10,1001,10,1,10,4,10,1008,8,0,10,4,10,101,0,8,181,2,4,17,10,1006,0,36,1,107,7,10,2,1008,0,10,3,8,1002,8,-1,10,1001,10,1,10,4,10,108,0,8,10,4,10,101,0,8,217,3,8,102,-1,8,10,1001,10,1,10,4,10,1008,8,0,10,4,10,101,0,8,240,1006,0,64,3,8,102,-1,8,10,1001,10,1,10,4,10,108,0,8,10,4,10,1002,8,1,264,3,8,1002,8,-1,10,1001,10,1,10,4,10,1008,8,1,10,4,10,1001,8,0,287,1,1104,15,10,1,102,8,10,1006,0,2,101,1,9,9,1007,9,940,10,1005,10,15,99,109,640,104,0,104,1,21102,932700857236,1,1,21101,335,0,0,1106,0,439,21101,0,387511792424,1,21101,346,0,0,1106,0,439,3,10,104,0,104,1,3,10,104,0,104,0,3,10,104,0,104,1,3,10,104,0,104,1,3,10,104,0,104,0,3,10,104,0,104,1,21101,46372252675,0,1,21102,393,1,0,1106,0,439,21101,97806162983,0,1,21102,404,1,0,1105,1,439,3,10,104,0,104,0,3,10,104,0,104,0,21102,1,825452438376,1,21101,0,427,0,1106,0,439,21102,709475586836,1,1,21101,0,438,0,1106,0,439,99,109,2,22101,0,-1,1,21101,40,0,2,21102,1,470,3,21102,1,460,0,1106,0,503,109,-2,2106,0,0,0,1,0,0,1,109,2,3,10,204,-1,1001,465,466,481,4,0,1001,465,1,465,108,4,465,10,1006,10,497,1101,0,0,465,109,-2,2105,1,0,0,109,4,2102,1,-1,502,1207,-3,0,10,1006,10,520,21102,1,0,-3,21202,-3,1,1,21202,-2,1,2,21101,0,1,3,21101,0,539,0,1106,0,544,109,-4,2105,1,0,109,5,1207,-3,1,10,1006,10,567,2207,-4,-2,10,1006,10,567,22101,0,-4,-4,1106,0,635,21202,-4,1,1,21201,-3,-1,2,21202,-2,2,3,21102,586,1,0,1105,1,544,22101,0,1,-4,21102,1,1,-1,2207,-4,-2,10,1006,10,605,21102,0,1,-1,22202,-2,-1,-2,2107,0,-3,10,1006,10,627,22101,0,-1,1,21102,1,627,0,106,0,502,21202,-2,-1,-2,22201,-4,-2,-4,109,-5,2105,1,0")?;
-    let mut map = Map::new();
+    let mut map = Map::new('.');
 
     let mut robot = Robot::new(brain);
 
-    map.set_to_char(&robot.position, '#');
+    map.set(robot.position.x as i64, robot.position.y as i64, '#');
 
     let mut running = true;
 
@@ -150,7 +146,13 @@ fn main() -> Result<()> {
 
     println!("Total painted: {}", count_painted_points(&map));
 
-    map.print();
+    print!(
+        "{}",
+        map.render(|ch| match ch {
+            '#' => '#',
+            _ => ' ',
+        })
+    );
 
     Ok(())
 }
@@ -0,0 +1,117 @@
+use std::cmp::{max, min};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Inclusive bounding box of every cell that has been set in a `SparseGrid`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bounds {
+    pub min_x: i64,
+    pub min_y: i64,
+    pub max_x: i64,
+    pub max_y: i64,
+}
+
+impl Bounds {
+    fn from_point(x: i64, y: i64) -> Self {
+        Bounds {
+            min_x: x,
+            min_y: y,
+            max_x: x,
+            max_y: y,
+        }
+    }
+
+    fn include(&mut self, x: i64, y: i64) {
+        self.min_x = min(self.min_x, x);
+        self.min_y = min(self.min_y, y);
+        self.max_x = max(self.max_x, x);
+        self.max_y = max(self.max_y, y);
+    }
+}
+
+/// An unbounded grid that only stores cells that have been explicitly set.
+///
+/// Reads of unset cells return the grid's default value. The bounding box grows as cells
+/// are set.
+#[derive(Clone, Debug)]
+pub struct SparseGrid<T> {
+    data: HashMap<(i64, i64), T>,
+    default: T,
+    bounds: Option<Bounds>,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new(default: T) -> Self {
+        SparseGrid {
+            data: HashMap::new(),
+            default,
+            bounds: None,
+        }
+    }
+
+    pub fn get(&self, x: i64, y: i64) -> &T {
+        self.data.get(&(x, y)).unwrap_or(&self.default)
+    }
+
+    pub fn set(&mut self, x: i64, y: i64, value: T) -> Option<T> {
+        match self.bounds.as_mut() {
+            Some(bounds) => bounds.include(x, y),
+            None => self.bounds = Some(Bounds::from_point(x, y)),
+        }
+
+        self.data.insert((x, y), value)
+    }
+
+    /// Iterates over explicitly set cells in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = ((i64, i64), &T)> {
+        self.data.iter().map(|(point, value)| (*point, value))
+    }
+
+    /// Iterates over row `y` from the left to the right edge of the bounding box, filling
+    /// unset cells with the default value.
+    pub fn row(&self, y: i64) -> impl Iterator<Item = (i64, &T)> {
+        let (min_x, max_x) = match self.bounds {
+            Some(bounds) => (bounds.min_x, bounds.max_x),
+            None => (0, -1),
+        };
+
+        (min_x..=max_x).map(move |x| (x, self.get(x, y)))
+    }
+
+    /// Renders the bounding box one line per row, mapping each tile to a character.
+    pub fn render<F>(&self, tile_char: F) -> Render<'_, T, F>
+    where
+        F: Fn(&T) -> char,
+    {
+        Render {
+            grid: self,
+            tile_char,
+        }
+    }
+}
+
+pub struct Render<'a, T, F> {
+    grid: &'a SparseGrid<T>,
+    tile_char: F,
+}
+
+impl<'a, T, F> fmt::Display for Render<'a, T, F>
+where
+    F: Fn(&T) -> char,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let bounds = match self.grid.bounds {
+            Some(bounds) => bounds,
+            None => return Ok(()),
+        };
+
+        for y in bounds.min_y..=bounds.max_y {
+            for (_, value) in self.grid.row(y) {
+                write!(f, "{}", (self.tile_char)(value))?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
 mod intcode;
+mod sparse_grid;
 
-use std::cmp::{max, min};
-use std::collections::{BTreeMap, VecDeque};
+use std::collections::VecDeque;
 use std::convert::{TryFrom, TryInto};
 use std::fmt;
 use std::str::FromStr;
@@ -9,8 +9,9 @@ use std::str::FromStr;
 use anyhow::{format_err, Error, Result};
 
 use crate::intcode::{Program, ProgramState};
+use crate::sparse_grid::SparseGrid;
 
-type Map = BTreeMap<i64, BTreeMap<i64, Tile>>;
+type Map = SparseGrid<Tile>;
 
 #[derive(Copy, Clone)]
 enum Tile {
@@ -52,54 +53,6 @@ impl fmt::Display for Tile {
     }
 }
 
-fn count_blocks(map: &Map) -> u64 {
-    let mut total = 0;
-    for row in map.values() {
-        for ch in row.values() {
-            if let Tile::Block = *ch {
-                total += 1;
-            }
-        }
-    }
-
-    total
-}
-
-fn print_map(map: &Map) {
-    let mut min_x = std::i64::MAX;
-    let mut max_x = std::i64::MIN;
-    let mut min_y = std::i64::MAX;
-    let mut max_y = std::i64::MIN;
-
-    for (y, row) in map.iter() {
-        for x in row.keys() {
-            min_x = min(*x, min_x);
-            max_x = max(*x, max_x);
-            min_y = min(*y, min_y);
-            max_y = max(*y, max_y);
-        }
-    }
-
-    for y in min_y..max_y + 1 {
-        for x in min_x..max_x + 1 {
-            let tile = *map
-                .get(&y)
-                .unwrap_or(&BTreeMap::new())
-                .get(&x)
-                .unwrap_or(&Tile::Empty);
-            print!("{}", tile);
-        }
-        println!("");
-    }
-}
-
-fn set_value(map: &mut Map, x: i64, y: i64, value: Tile) {
-    *map.entry(y)
-        .or_insert(BTreeMap::new())
-        .entry(x)
-        .or_insert(Tile::Empty) = value;
-}
-
 fn main() -> Result<()> {
     env_logger::from_env(env_logger::Env::default().default_filter_or("info")).init();
 
@@ -111,7 +64,7 @@ fn main() -> Result<()> {
 
     program.set_memory_value(0, 2)?;
 
-    let mut map = BTreeMap::new();
+    let mut map = Map::new();
     let mut score = 0;
 
     let mut paddle_x = 0;
@@ -142,7 +95,7 @@ fn main() -> Result<()> {
                     ball_x = x;
                 }
 
-                set_value(&mut map, x, y, tile);
+                map.set(x, y, tile);
             }
         }
 
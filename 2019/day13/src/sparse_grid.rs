@@ -0,0 +1,19 @@
+use std::collections::HashMap;
+
+/// An unbounded grid that only stores cells that have been explicitly set.
+#[derive(Clone, Debug)]
+pub struct SparseGrid<T> {
+    data: HashMap<(i64, i64), T>,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        SparseGrid {
+            data: HashMap::new(),
+        }
+    }
+
+    pub fn set(&mut self, x: i64, y: i64, value: T) -> Option<T> {
+        self.data.insert((x, y), value)
+    }
+}
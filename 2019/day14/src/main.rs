@@ -1,12 +1,17 @@
-use std::cmp::Ordering;
+mod dot;
+mod stoichiometry;
+
 use std::collections::BTreeMap;
 use std::fmt;
 use std::str::FromStr;
 
 use anyhow::Result;
-use log::{debug, error};
+use log::debug;
 use thiserror::Error;
 
+use dot::Dot;
+use stoichiometry::Reactions;
+
 #[derive(Error, Debug)]
 enum ParseComponentError {
     #[error("The passed source has the wrong number of parts")]
@@ -132,116 +137,61 @@ fn build_reaction_map(reactions: &Vec<Reaction>) -> BTreeMap<String, Reaction> {
     reaction_map
 }
 
-fn lowest_ore_cost_for_fuel(reaction_map: &BTreeMap<String, Reaction>, required_fuel: u64) -> u64 {
-    let mut needed = BTreeMap::new();
-    needed.insert("FUEL", required_fuel);
-
-    let mut extra = BTreeMap::new();
-
-    loop {
-        let choices: Vec<&str> = needed.keys().cloned().filter(|k| *k != "ORE").collect();
-
-        if choices.len() == 0 {
-            break;
-        }
-
-        let next = choices.into_iter().next().unwrap();
-
-        if next == "ORE" {
-            continue;
+/// The longest chain of reactions from `name` down to ORE, starting with `name`.
+fn longest_chain(reaction_map: &BTreeMap<String, Reaction>, name: &str) -> Vec<String> {
+    let mut longest = Vec::new();
+    if let Some(reaction) = reaction_map.get(name) {
+        for component in reaction.components.iter() {
+            let chain = longest_chain(reaction_map, &component.name);
+            if chain.len() > longest.len() {
+                longest = chain;
+            }
         }
+    }
 
-        debug!("Checking {}", next);
-
-        let quantity_needed = match needed.get(&next) {
-            Some(quantity) => *quantity,
-            None => break,
-        };
-
-        let reaction = reaction_map.get(next).unwrap();
-
-        let output = reaction.result.quantity;
+    longest.insert(0, name.to_string());
+    longest
+}
 
-        let mut multiplier = quantity_needed / output;
-        let remainder = quantity_needed % output;
-        if remainder > 0 {
-            multiplier += 1;
-        }
-        /*
-        debug!("NEEDED {}, OUTPUT: {}", quantity_needed, output);
-        debug!(
-            "AS F32 NEEDED {}, OUTPUT {}",
-            quantity_needed as f32, output as f32
-        );
-        debug!("DIV: {}", quantity_needed as f32 / output as f32);
-        */
-        //let multiplier = (quantity_needed as f32 / output as f32).ceil() as u64;
-
-        debug!(
-            "Generated {} {}, multiplier {}",
-            output * multiplier,
-            next,
-            multiplier
+/// Each chemical with an edge to every input of the reaction producing it, labelled with the
+/// quantity used. The longest chain from FUEL to ORE is highlighted.
+fn reactions_to_dot(reaction_map: &BTreeMap<String, Reaction>) -> Dot {
+    let mut dot = Dot::new("reactions");
+    for reaction in reaction_map.values() {
+        dot.label(
+            &reaction.result.name,
+            format!("{} ({})", reaction.result.name, reaction.result.quantity),
         );
-
-        let quantity_generated = output * multiplier;
-        if quantity_generated < quantity_needed {
-            error!(
-                "For {}: needed {}, but only generated {}",
-                next, quantity_needed, quantity_generated
-            );
-        }
-        // Mark any extra we've generated
-        *extra.entry(next.to_string()).or_insert(0) += quantity_generated - quantity_needed;
-
-        // Remove the stuff we've generated
-        needed.remove(&next);
-
         for component in reaction.components.iter() {
-            // Pull from extra before requiring generation
-            let mut component_needed = component.quantity * multiplier;
-            let mut component_extra = *extra.get(&component.name).unwrap_or(&0);
-            debug!("Need {}", component.name);
-            if component_extra > 0 {
-                debug!("Have extra {}", component.name);
-                if component_needed >= component_extra {
-                    debug!("Using all extra {}", component.name);
-                    component_needed -= component_extra;
-                    component_extra = 0;
-                } else {
-                    debug!("Using {} extra {}", component_needed, component.name);
-                    component_extra -= component_needed;
-                    component_needed = 0;
-                }
-            }
-
-            debug!("Still need {} {}", component_needed, component.name);
-
-            extra.insert(component.name.clone(), component_extra);
-            *needed.entry(&component.name).or_insert(0) += component_needed;
+            dot.edge(
+                &reaction.result.name,
+                &component.name,
+                Some(component.quantity.to_string()),
+            );
         }
     }
 
-    *needed.get("ORE").unwrap()
-}
+    dot.node("ORE");
+    dot.highlight_path(&longest_chain(reaction_map, "FUEL"));
 
-fn search(reaction_map: &BTreeMap<String, Reaction>, low: u64, high: u64) -> u64 {
-    let guess = (low + high) / 2;
-    let cost = lowest_ore_cost_for_fuel(reaction_map, guess);
-
-    if high <= low {
-        return guess;
-    }
+    dot
+}
 
-    if high - low <= 1 {
-        return guess;
+fn build_reactions(reactions: &[Reaction]) -> Result<Reactions> {
+    let mut built = Reactions::new();
+    for reaction in reactions.iter() {
+        built.add(
+            &reaction.result.name,
+            reaction.result.quantity,
+            reaction
+                .components
+                .iter()
+                .map(|component| (component.name.clone(), component.quantity))
+                .collect(),
+        )?;
     }
 
-    match cost.cmp(&1000000000000) {
-        Ordering::Less => search(reaction_map, guess, high),
-        Ordering::Greater => search(reaction_map, low, guess),
-        Ordering::Equal => guess,
-    }
+    Ok(built)
 }
 
 fn main() -> Result<()> {
@@ -251,12 +201,23 @@ fn main() -> Result<()> {
 
     let reaction_map = build_reaction_map(&reactions);
 
-    let cost_for_one = lowest_ore_cost_for_fuel(&reaction_map, 1);
+    if std::env::args().any(|arg| arg == "--dot") {
+        print!("{}", reactions_to_dot(&reaction_map));
+        return Ok(());
+    }
 
-    let estimated_top = (1000000000000 / cost_for_one) * 2;
-    let estimated_bottom = (1000000000000 / cost_for_one) / 2;
+    let reactions = build_reactions(&reactions)?;
 
-    let result = search(&reaction_map, estimated_bottom, estimated_top);
+    let production = reactions.produce("FUEL", 1)?;
+    for (product, runs) in production.runs.iter() {
+        debug!("{} runs {} times", product, runs);
+    }
+    println!(
+        "Ore for one fuel: {}",
+        production.base.get("ORE").copied().unwrap_or(0)
+    );
+
+    let result = reactions.max_producible("ORE", 1000000000000, "FUEL")?;
 
     println!("Fuel for one trillion: {}", result);
 
@@ -268,11 +229,15 @@ mod tests {
     use super::*;
 
     fn run_test(filename: &str) -> Result<u64> {
-        let reactions = read_input(filename)?;
+        let reactions = build_reactions(&read_input(filename)?)?;
+
+        reactions.cost("ORE", "FUEL", 1)
+    }
 
-        let reaction_map = build_reaction_map(&reactions);
+    fn run_max_test(filename: &str) -> Result<u64> {
+        let reactions = build_reactions(&read_input(filename)?)?;
 
-        Ok(lowest_ore_cost_for_fuel(&reaction_map, 1))
+        reactions.max_producible("ORE", 1000000000000, "FUEL")
     }
 
     #[test]
@@ -292,6 +257,7 @@ mod tests {
     #[test]
     fn test_3() -> Result<()> {
         assert_eq!(run_test("test3.txt")?, 13312);
+        assert_eq!(run_max_test("test3.txt")?, 82892753);
 
         Ok(())
     }
@@ -299,6 +265,7 @@ mod tests {
     #[test]
     fn test_4() -> Result<()> {
         assert_eq!(run_test("test4.txt")?, 180697);
+        assert_eq!(run_max_test("test4.txt")?, 5586022);
 
         Ok(())
     }
@@ -306,6 +273,7 @@ mod tests {
     #[test]
     fn test_5() -> Result<()> {
         assert_eq!(run_test("test5.txt")?, 2210736);
+        assert_eq!(run_max_test("test5.txt")?, 460664);
 
         Ok(())
     }
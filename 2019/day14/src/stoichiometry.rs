@@ -0,0 +1,261 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{format_err, Result};
+
+/// One reaction: running it once turns `inputs` into `output` units of its product.
+#[derive(Clone, Debug)]
+struct Recipe {
+    output: u64,
+    inputs: Vec<(String, u64)>,
+}
+
+/// What it takes to make some quantity of a chemical.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Production {
+    /// How much of each base chemical (one no reaction produces) is consumed.
+    pub base: BTreeMap<String, u64>,
+    /// How many times each reaction runs, keyed by its product.
+    pub runs: BTreeMap<String, u64>,
+    /// Whatever is made beyond what is needed, keyed by chemical.
+    pub leftovers: BTreeMap<String, u64>,
+}
+
+/// A set of reactions, each the only way to make its product.
+#[derive(Clone, Debug, Default)]
+pub struct Reactions {
+    recipes: BTreeMap<String, Recipe>,
+}
+
+impl Reactions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the reaction making `output` of `product` from `inputs`. Fails if `product`
+    /// already has a reaction.
+    pub fn add(&mut self, product: &str, output: u64, inputs: Vec<(String, u64)>) -> Result<()> {
+        if output == 0 {
+            return Err(format_err!("The reaction for {} makes nothing", product));
+        }
+
+        if self.recipes.contains_key(product) {
+            return Err(format_err!("{} is made by more than one reaction", product));
+        }
+
+        self.recipes
+            .insert(product.to_string(), Recipe { output, inputs });
+        Ok(())
+    }
+
+    /// Every chemical `target` is made from, each listed before all of its inputs.
+    fn order_from(&self, target: &str) -> Result<Vec<&str>> {
+        fn visit<'a>(
+            reactions: &'a Reactions,
+            chemical: &'a str,
+            visiting: &mut BTreeSet<&'a str>,
+            done: &mut BTreeSet<&'a str>,
+            order: &mut Vec<&'a str>,
+        ) -> Result<()> {
+            if done.contains(chemical) {
+                return Ok(());
+            }
+            if !visiting.insert(chemical) {
+                return Err(format_err!("{} is needed to make itself", chemical));
+            }
+
+            if let Some(recipe) = reactions.recipes.get(chemical) {
+                for (input, _) in recipe.inputs.iter() {
+                    visit(reactions, input, visiting, done, order)?;
+                }
+            }
+
+            visiting.remove(chemical);
+            done.insert(chemical);
+            order.push(chemical);
+            Ok(())
+        }
+
+        let (mut visiting, mut done, mut order) = (BTreeSet::new(), BTreeSet::new(), Vec::new());
+        let target = self
+            .recipes
+            .get_key_value(target)
+            .map(|(name, _)| name.as_str())
+            .ok_or_else(|| format_err!("No reaction makes {}", target))?;
+        visit(self, target, &mut visiting, &mut done, &mut order)?;
+
+        order.reverse();
+        Ok(order)
+    }
+
+    /// Works out how to make `quantity` of `target` with as little of every base chemical as
+    /// possible. Each chemical is only settled once everything that uses it has been, so
+    /// reactions never run more often than they must.
+    pub fn produce(&self, target: &str, quantity: u64) -> Result<Production> {
+        self.try_produce(target, quantity)?.ok_or_else(|| {
+            format_err!(
+                "Making {} {} needs more of something than fits in a u64",
+                quantity,
+                target
+            )
+        })
+    }
+
+    /// Like `produce`, but `None` if any amount involved would overflow.
+    fn try_produce(&self, target: &str, quantity: u64) -> Result<Option<Production>> {
+        let mut needed: BTreeMap<&str, u64> = BTreeMap::new();
+        needed.insert(target, quantity);
+
+        let mut production = Production::default();
+        for chemical in self.order_from(target)? {
+            let amount = needed.get(chemical).copied().unwrap_or(0);
+
+            let recipe = match self.recipes.get(chemical) {
+                Some(recipe) => recipe,
+                None => {
+                    production.base.insert(chemical.to_string(), amount);
+                    continue;
+                }
+            };
+
+            let runs = amount.div_ceil(recipe.output);
+            let made = match runs.checked_mul(recipe.output) {
+                Some(made) => made,
+                None => return Ok(None),
+            };
+            production.runs.insert(chemical.to_string(), runs);
+            if made > amount {
+                production
+                    .leftovers
+                    .insert(chemical.to_string(), made - amount);
+            }
+
+            for (input, input_quantity) in recipe.inputs.iter() {
+                let total = needed.entry(input).or_insert(0);
+                *total = match runs
+                    .checked_mul(*input_quantity)
+                    .and_then(|extra| total.checked_add(extra))
+                {
+                    Some(total) => total,
+                    None => return Ok(None),
+                };
+            }
+        }
+
+        Ok(Some(production))
+    }
+
+    /// The least of `base` needed to make `quantity` of `target`.
+    pub fn cost(&self, base: &str, target: &str, quantity: u64) -> Result<u64> {
+        Ok(self
+            .produce(target, quantity)?
+            .base
+            .get(base)
+            .copied()
+            .unwrap_or(0))
+    }
+
+    /// Whether `quantity` of `target` can be made from `budget` of `base`. Quantities too big
+    /// to work out without overflowing can't be.
+    fn affordable(&self, base: &str, budget: u64, target: &str, quantity: u64) -> Result<bool> {
+        Ok(match self.try_produce(target, quantity)? {
+            Some(production) => production.base.get(base).copied().unwrap_or(0) <= budget,
+            None => false,
+        })
+    }
+
+    /// The most `target` that can be made from `budget` of `base`.
+    ///
+    /// Making n units never costs more than n times the cost of one, so budget / cost(1) is
+    /// always affordable. Leftovers can make further units cheaper, so the search steps up
+    /// from there in growing strides until a quantity isn't affordable, then bisects.
+    pub fn max_producible(&self, base: &str, budget: u64, target: &str) -> Result<u64> {
+        let cost_of_one = self.cost(base, target, 1)?;
+        if cost_of_one == 0 {
+            return Err(format_err!("{} can be made without any {}", target, base));
+        }
+
+        let mut affordable = budget / cost_of_one;
+        let mut stride = affordable.max(1);
+        let mut unaffordable = loop {
+            let next = affordable.saturating_add(stride);
+            if !self.affordable(base, budget, target, next)? {
+                break next;
+            }
+            if next == u64::MAX {
+                return Ok(next);
+            }
+
+            affordable = next;
+            stride = stride.saturating_mul(2);
+        };
+
+        while unaffordable - affordable > 1 {
+            let middle = affordable + (unaffordable - affordable) / 2;
+            if self.affordable(base, budget, target, middle)? {
+                affordable = middle;
+            } else {
+                unaffordable = middle;
+            }
+        }
+
+        Ok(affordable)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Result<Reactions> {
+        let mut reactions = Reactions::new();
+        reactions.add("A", 10, vec![("ORE".to_string(), 10)])?;
+        reactions.add("B", 1, vec![("ORE".to_string(), 1)])?;
+        reactions.add("C", 1, vec![("A".to_string(), 7), ("B".to_string(), 1)])?;
+        reactions.add("D", 1, vec![("A".to_string(), 7), ("C".to_string(), 1)])?;
+        reactions.add("E", 1, vec![("A".to_string(), 7), ("D".to_string(), 1)])?;
+        reactions.add("FUEL", 1, vec![("A".to_string(), 7), ("E".to_string(), 1)])?;
+
+        Ok(reactions)
+    }
+
+    #[test]
+    fn test_produce() -> Result<()> {
+        let production = example()?.produce("FUEL", 1)?;
+
+        assert_eq!(production.base.get("ORE"), Some(&31));
+        assert_eq!(production.runs.get("A"), Some(&3));
+        assert_eq!(production.leftovers.get("A"), Some(&2));
+        assert_eq!(production.leftovers.get("FUEL"), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_producible() -> Result<()> {
+        let reactions = example()?;
+
+        // Each FUEL takes 28 A and 1 B, so 31 ORE makes one and 59 ORE still only makes one.
+        assert_eq!(reactions.max_producible("ORE", 59, "FUEL")?, 1);
+        assert_eq!(reactions.max_producible("ORE", 58 * 100, "FUEL")?, 200);
+        assert_eq!(reactions.max_producible("ORE", 30, "FUEL")?, 0);
+
+        let mut reactions = Reactions::new();
+        reactions.add("FUEL", 1, vec![("ORE".to_string(), 1)])?;
+        assert_eq!(reactions.max_producible("ORE", u64::MAX, "FUEL")?, u64::MAX);
+
+        // One ORE makes a thousand FUEL, so the answer is far beyond the budget.
+        let mut reactions = Reactions::new();
+        reactions.add("FUEL", 1000, vec![("ORE".to_string(), 1)])?;
+        assert_eq!(reactions.max_producible("ORE", 5, "FUEL")?, 5000);
+
+        let mut reactions = Reactions::new();
+        reactions.add("FUEL", 1, vec![("ORE".to_string(), 2)])?;
+        assert!(reactions.produce("FUEL", u64::MAX).is_err());
+        assert_eq!(
+            reactions.max_producible("ORE", u64::MAX, "FUEL")?,
+            u64::MAX / 2
+        );
+
+        Ok(())
+    }
+}
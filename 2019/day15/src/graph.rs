@@ -0,0 +1,92 @@
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+/// A directed graph with non-negative edge costs, explored lazily from its nodes.
+pub trait Graph {
+    type Node: Clone + Eq + Hash;
+
+    /// The nodes one edge away from `node`, with the cost of each edge.
+    fn neighbors(&self, node: &Self::Node) -> Vec<(Self::Node, u64)>;
+}
+
+/// Costs and shortest-path parents of every node settled by a search.
+#[derive(Debug)]
+pub struct Search<N> {
+    pub costs: HashMap<N, u64>,
+    parents: HashMap<N, N>,
+    /// The first target reached, if the search stopped at one.
+    pub target: Option<N>,
+}
+
+impl<N: Clone + Eq + Hash> Search<N> {
+    fn new() -> Self {
+        Search {
+            costs: HashMap::new(),
+            parents: HashMap::new(),
+            target: None,
+        }
+    }
+
+    pub fn cost(&self, node: &N) -> Option<u64> {
+        self.costs.get(node).copied()
+    }
+
+    /// The cost of reaching the target the search stopped at.
+    pub fn target_cost(&self) -> Option<u64> {
+        self.target.as_ref().and_then(|target| self.cost(target))
+    }
+
+    /// The nodes from the nearest source to `node`, inclusive of both.
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        if !self.costs.contains_key(node) {
+            return None;
+        }
+
+        let mut path = vec![node.clone()];
+        while let Some(parent) = self.parents.get(&path[path.len() - 1]) {
+            path.push(parent.clone());
+        }
+        path.reverse();
+
+        Some(path)
+    }
+}
+
+/// Breadth-first search treating every edge as cost 1. Stops at the first node matching
+/// `is_target`; pass `|_| false` to reach everything.
+pub fn bfs<G, I, F>(graph: &G, sources: I, is_target: F) -> Search<G::Node>
+where
+    G: Graph,
+    I: IntoIterator<Item = G::Node>,
+    F: Fn(&G::Node) -> bool,
+{
+    let mut search = Search::new();
+    let mut to_visit = VecDeque::new();
+
+    for source in sources {
+        if !search.costs.contains_key(&source) {
+            search.costs.insert(source.clone(), 0);
+            to_visit.push_back(source);
+        }
+    }
+
+    while let Some(node) = to_visit.pop_front() {
+        if is_target(&node) {
+            search.target = Some(node);
+            break;
+        }
+
+        let cost = search.costs[&node];
+        for (neighbor, _) in graph.neighbors(&node) {
+            if search.costs.contains_key(&neighbor) {
+                continue;
+            }
+
+            search.costs.insert(neighbor.clone(), cost + 1);
+            search.parents.insert(neighbor.clone(), node.clone());
+            to_visit.push_back(neighbor);
+        }
+    }
+
+    search
+}
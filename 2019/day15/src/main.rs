@@ -1,74 +1,37 @@
+mod graph;
 mod intcode;
 mod point;
+mod sparse_grid;
 
-use std::cmp::{max, min};
-use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::collections::{BTreeSet, VecDeque};
 use std::convert::{TryFrom, TryInto};
 use std::fmt;
 
 use anyhow::{format_err, Error, Result};
 use log::debug;
 
+use graph::{bfs, Graph};
 use intcode::Program;
 use point::Point;
+use sparse_grid::SparseGrid;
 
-// TODO(jsvana): Make a generic?
 struct Map {
-    data: BTreeMap<i64, BTreeMap<i64, Tile>>,
+    data: SparseGrid<Tile>,
 }
 
 impl Map {
     fn new() -> Self {
         Self {
-            data: BTreeMap::new(),
+            data: SparseGrid::new(Tile::Unknown),
         }
     }
 
     fn set_point(&mut self, point: &Point, tile: &Tile) {
-        self.data
-            .entry(point.y)
-            .or_insert(BTreeMap::new())
-            .insert(point.x, tile.clone());
+        self.data.set(point.x, point.y, tile.clone());
     }
 
     fn get_point(&self, point: &Point) -> Tile {
-        self.data
-            .get(&point.y)
-            .unwrap_or(&BTreeMap::new())
-            .get(&point.x)
-            .unwrap_or(&Tile::Unknown)
-            .clone()
-    }
-
-    fn to_string(&self, robot_position: &Point) -> String {
-        let mut top_left = Point::max();
-        let mut bottom_right = Point::min();
-
-        for (y, row) in self.data.iter() {
-            for x in row.keys() {
-                top_left.x = min(top_left.x, *x);
-                top_left.y = min(top_left.y, *y);
-                bottom_right.x = max(bottom_right.x, *x);
-                bottom_right.y = max(bottom_right.y, *y);
-            }
-        }
-
-        let row_size = bottom_right.x - top_left.x + 1;
-
-        let mut rows = Vec::new();
-        for y in top_left.y..bottom_right.y + 1 {
-            let mut row = String::with_capacity(row_size as usize);
-            for x in top_left.x..bottom_right.x + 1 {
-                if robot_position.x == x && robot_position.y == y {
-                    row.push('R');
-                } else {
-                    row.push(self.get_point(&Point { x, y }).into());
-                }
-            }
-            rows.push(row);
-        }
-
-        rows.join("\n")
+        self.data.get(point.x, point.y).clone()
     }
 }
 
@@ -110,6 +73,13 @@ enum Direction {
     East,
 }
 
+const DIRECTIONS: [Direction; 4] = [
+    Direction::North,
+    Direction::South,
+    Direction::East,
+    Direction::West,
+];
+
 impl fmt::Display for Direction {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -196,55 +166,38 @@ fn move_robot(robot: &mut Point, direction: &Direction) {
     }
 }
 
-fn get_path(map: &Map, start: &Point, end: &Point) -> Option<Vec<Direction>> {
-    let directions = vec![
-        Direction::North,
-        Direction::South,
-        Direction::East,
-        Direction::West,
-    ];
+/// Floor and oxygen tiles connect to their open cardinal neighbours.
+impl Graph for Map {
+    type Node = (i64, i64);
 
-    let mut visited = BTreeSet::new();
+    fn neighbors(&self, node: &(i64, i64)) -> Vec<((i64, i64), u64)> {
+        let point = Point::from_tuple(node);
 
-    let mut to_visit = VecDeque::new();
-    let mut paths = VecDeque::new();
+        DIRECTIONS
+            .iter()
+            .map(|direction| point_in_direction(&point, direction))
+            .filter(|next_point| !matches!(self.get_point(next_point), Tile::Unknown | Tile::Wall))
+            .map(|next_point| (next_point.as_tuple(), 1))
+            .collect()
+    }
+}
 
-    to_visit.push_back(start.as_tuple());
-    paths.push_back(Vec::new());
+fn direction_between(from: &(i64, i64), to: &(i64, i64)) -> Option<Direction> {
+    DIRECTIONS
+        .iter()
+        .find(|direction| point_in_direction(&Point::from_tuple(from), direction).as_tuple() == *to)
+        .cloned()
+}
 
+fn get_path(map: &Map, start: &Point, end: &Point) -> Option<Vec<Direction>> {
     let end_tuple = end.as_tuple();
+    let search = bfs(map, vec![start.as_tuple()], |node| *node == end_tuple);
+    let nodes = search.path_to(&end_tuple)?;
 
-    while !to_visit.is_empty() {
-        let next = to_visit.pop_front().unwrap();
-        let next_path = paths.pop_front().unwrap();
-
-        if visited.contains(&next) {
-            continue;
-        }
-
-        visited.insert(next);
-
-        if next == end_tuple {
-            return Some(next_path);
-        }
-
-        for direction in directions.iter() {
-            let next_point = point_in_direction(&Point::from_tuple(&next), direction);
-            if let Tile::Unknown | Tile::Wall = map.get_point(&next_point) {
-                continue;
-            }
-
-            let mut further_path = next_path.clone();
-            further_path.push(direction.clone());
-
-            let point_tuple = next_point.as_tuple();
-            to_visit.push_back(point_tuple);
-
-            paths.push_back(further_path);
-        }
-    }
-
-    None
+    nodes
+        .windows(2)
+        .map(|pair| direction_between(&pair[0], &pair[1]))
+        .collect()
 }
 
 fn follow_path(program: &mut Program, robot: &mut Point, path: &Vec<Direction>) -> Result<()> {
@@ -369,102 +322,17 @@ fn populate_map(program: &mut Program, map: &mut Map, start: &Point) -> Result<O
 }
 
 fn count_shortest_path(map: &Map, start: &Point, end: &Point) -> Option<u64> {
-    let directions = vec![
-        Direction::North,
-        Direction::South,
-        Direction::East,
-        Direction::West,
-    ];
-
-    let mut scores = BTreeMap::new();
-
-    let mut visited = BTreeSet::new();
-
-    let mut to_visit = VecDeque::new();
-
-    let start_tuple = start.as_tuple();
-    to_visit.push_back(start_tuple);
-
-    scores.insert(start_tuple, 0);
-
-    while !to_visit.is_empty() {
-        let next = to_visit.pop_front().unwrap();
-
-        if visited.contains(&next) {
-            continue;
-        }
-
-        visited.insert(next);
-
-        let point_score = scores.get(&next).unwrap().clone();
-
-        for direction in directions.iter() {
-            let next_point = point_in_direction(&Point::from_tuple(&next), direction);
-            if let Tile::Unknown | Tile::Wall = map.get_point(&next_point) {
-                continue;
-            }
-
-            let point_tuple = next_point.as_tuple();
-            to_visit.push_back(point_tuple);
-
-            let next_score = scores.get(&point_tuple).unwrap_or(&std::u64::MAX).clone();
-            scores.insert(point_tuple, min(next_score, point_score + 1));
-        }
-    }
-
-    scores.get(&end.as_tuple()).cloned()
+    let end_tuple = end.as_tuple();
+    bfs(map, vec![start.as_tuple()], |node| *node == end_tuple).target_cost()
 }
 
-fn visit_all(map: &mut Map, start: &Point) -> u64 {
-    let directions = vec![
-        Direction::North,
-        Direction::South,
-        Direction::East,
-        Direction::West,
-    ];
-
-    let mut scores = BTreeMap::new();
-
-    let mut visited = BTreeSet::new();
-
-    let mut to_visit = VecDeque::new();
-
-    let start_tuple = start.as_tuple();
-    to_visit.push_back(start_tuple);
-
-    scores.insert(start_tuple, 0);
-
-    let mut max_count = 0;
-
-    while !to_visit.is_empty() {
-        let next = to_visit.pop_front().unwrap();
-
-        if visited.contains(&next) {
-            continue;
-        }
-
-        visited.insert(next);
-
-        let point_score = scores.get(&next).unwrap().clone();
-
-        for direction in directions.iter() {
-            let next_point = point_in_direction(&Point::from_tuple(&next), direction);
-            if let Tile::Unknown | Tile::Wall = map.get_point(&next_point) {
-                continue;
-            }
-
-            let point_tuple = next_point.as_tuple();
-            to_visit.push_back(point_tuple);
-
-            let mut next_score = scores.get(&point_tuple).unwrap_or(&std::u64::MAX).clone();
-            next_score = min(next_score, point_score + 1);
-            max_count = max(max_count, next_score);
-
-            scores.insert(point_tuple, next_score);
-        }
-    }
-
-    max_count
+fn visit_all(map: &Map, start: &Point) -> u64 {
+    bfs(map, vec![start.as_tuple()], |_| false)
+        .costs
+        .values()
+        .copied()
+        .max()
+        .unwrap_or(0)
 }
 
 fn main() -> Result<()> {
@@ -483,7 +351,7 @@ fn main() -> Result<()> {
                 "Shortest_path: {}",
                 count_shortest_path(&map, &Point::zero(), &oxygen_point).unwrap()
             );
-            println!("Minutes to fill: {}", visit_all(&mut map, &oxygen_point));
+            println!("Minutes to fill: {}", visit_all(&map, &oxygen_point));
         }
         None => println!("Unable to find oxygen"),
     }
@@ -0,0 +1,26 @@
+use std::collections::HashMap;
+
+/// An unbounded grid that only stores cells that have been explicitly set. Reads of unset
+/// cells return the grid's default value.
+#[derive(Clone, Debug)]
+pub struct SparseGrid<T> {
+    data: HashMap<(i64, i64), T>,
+    default: T,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new(default: T) -> Self {
+        SparseGrid {
+            data: HashMap::new(),
+            default,
+        }
+    }
+
+    pub fn get(&self, x: i64, y: i64) -> &T {
+        self.data.get(&(x, y)).unwrap_or(&self.default)
+    }
+
+    pub fn set(&mut self, x: i64, y: i64, value: T) -> Option<T> {
+        self.data.insert((x, y), value)
+    }
+}
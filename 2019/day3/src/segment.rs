@@ -0,0 +1,216 @@
+fn cross(a: (i64, i64), b: (i64, i64)) -> i64 {
+    a.0 * b.1 - a.1 * b.0
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// A straight line between two lattice points, including both ends.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Segment {
+    pub start: (i64, i64),
+    pub end: (i64, i64),
+}
+
+/// Where two segments meet. Only lattice points count, so crossing lines may not intersect.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Intersection {
+    None,
+    Point((i64, i64)),
+    /// The segments are collinear and share every lattice point of this segment.
+    Overlap(Segment),
+}
+
+impl Intersection {
+    pub fn points(&self) -> Vec<(i64, i64)> {
+        match self {
+            Intersection::None => Vec::new(),
+            Intersection::Point(point) => vec![*point],
+            Intersection::Overlap(segment) => segment.lattice_points(),
+        }
+    }
+}
+
+impl Segment {
+    pub fn new(start: (i64, i64), end: (i64, i64)) -> Self {
+        Self { start, end }
+    }
+
+    fn delta(&self) -> (i64, i64) {
+        (self.end.0 - self.start.0, self.end.1 - self.start.1)
+    }
+
+    /// The smallest lattice step along the segment, or (0, 0) for a single point.
+    fn primitive_step(&self) -> ((i64, i64), i64) {
+        let (dx, dy) = self.delta();
+        let divisor = gcd(dx, dy);
+        if divisor == 0 {
+            return ((0, 0), 0);
+        }
+
+        ((dx / divisor, dy / divisor), divisor)
+    }
+
+    /// The number of king moves from one end to the other.
+    pub fn steps(&self) -> i64 {
+        let (dx, dy) = self.delta();
+        std::cmp::max(dx.abs(), dy.abs())
+    }
+
+    /// The points lying exactly on the segment.
+    pub fn lattice_points(&self) -> Vec<(i64, i64)> {
+        let (step, count) = self.primitive_step();
+        (0..=count)
+            .map(|k| (self.start.0 + step.0 * k, self.start.1 + step.1 * k))
+            .collect()
+    }
+
+    pub fn contains(&self, point: (i64, i64)) -> bool {
+        let offset = (point.0 - self.start.0, point.1 - self.start.1);
+        cross(offset, self.delta()) == 0
+            && point.0 >= std::cmp::min(self.start.0, self.end.0)
+            && point.0 <= std::cmp::max(self.start.0, self.end.0)
+            && point.1 >= std::cmp::min(self.start.1, self.end.1)
+            && point.1 <= std::cmp::max(self.start.1, self.end.1)
+    }
+
+    /// King moves from the start to `point`, if it lies on the segment.
+    pub fn steps_to(&self, point: (i64, i64)) -> Option<i64> {
+        if !self.contains(point) {
+            return None;
+        }
+
+        Some(Segment::new(self.start, point).steps())
+    }
+
+    /// The position of a collinear lattice point, in primitive steps from the start.
+    fn position_of(&self, point: (i64, i64), step: (i64, i64)) -> i64 {
+        if step.0 != 0 {
+            (point.0 - self.start.0) / step.0
+        } else {
+            (point.1 - self.start.1) / step.1
+        }
+    }
+
+    pub fn intersection(&self, other: &Segment) -> Intersection {
+        let (r, s) = (self.delta(), other.delta());
+
+        if r == (0, 0) || s == (0, 0) {
+            let (point, segment) = if r == (0, 0) {
+                (self.start, other)
+            } else {
+                (other.start, self)
+            };
+
+            return if segment.contains(point) {
+                Intersection::Point(point)
+            } else {
+                Intersection::None
+            };
+        }
+
+        let offset = (other.start.0 - self.start.0, other.start.1 - self.start.1);
+        let denominator = cross(r, s);
+
+        if denominator == 0 {
+            if cross(offset, r) != 0 {
+                return Intersection::None;
+            }
+
+            // Collinear: both segments' lattice points are whole steps along the same line.
+            let (step, count) = self.primitive_step();
+            let a = self.position_of(other.start, step);
+            let b = self.position_of(other.end, step);
+            let low = std::cmp::max(0, std::cmp::min(a, b));
+            let high = std::cmp::min(count, std::cmp::max(a, b));
+
+            let at = |k: i64| (self.start.0 + step.0 * k, self.start.1 + step.1 * k);
+            return match low.cmp(&high) {
+                std::cmp::Ordering::Greater => Intersection::None,
+                std::cmp::Ordering::Equal => Intersection::Point(at(low)),
+                std::cmp::Ordering::Less => Intersection::Overlap(Segment::new(at(low), at(high))),
+            };
+        }
+
+        let (mut t, mut u, mut denominator) = (cross(offset, s), cross(offset, r), denominator);
+        if denominator < 0 {
+            t = -t;
+            u = -u;
+            denominator = -denominator;
+        }
+
+        if t < 0 || t > denominator || u < 0 || u > denominator {
+            return Intersection::None;
+        }
+
+        if (r.0 * t) % denominator != 0 || (r.1 * t) % denominator != 0 {
+            return Intersection::None;
+        }
+
+        Intersection::Point((
+            self.start.0 + r.0 * t / denominator,
+            self.start.1 + r.1 * t / denominator,
+        ))
+    }
+}
+
+/// Connected segments walked in order, such as a wire or a rock formation.
+#[derive(Clone, Debug, Default)]
+pub struct Path {
+    pub segments: Vec<Segment>,
+}
+
+impl Path {
+    /// A path through each of `points` in turn.
+    pub fn through(points: &[(i64, i64)]) -> Self {
+        Self {
+            segments: points
+                .windows(2)
+                .map(|pair| Segment::new(pair[0], pair[1]))
+                .collect(),
+        }
+    }
+
+    /// A path from `start` following relative moves.
+    pub fn from_moves(start: (i64, i64), moves: &[(i64, i64)]) -> Self {
+        let mut points = vec![start];
+        for (dx, dy) in moves {
+            let (x, y) = points[points.len() - 1];
+            points.push((x + dx, y + dy));
+        }
+
+        Self::through(&points)
+    }
+
+    /// King moves along the path until `point` is first reached.
+    pub fn steps_to(&self, point: (i64, i64)) -> Option<i64> {
+        let mut walked = 0;
+        for segment in self.segments.iter() {
+            if let Some(steps) = segment.steps_to(point) {
+                return Some(walked + steps);
+            }
+            walked += segment.steps();
+        }
+
+        None
+    }
+
+    /// Every lattice point shared with `other`, without duplicates, in no particular order.
+    pub fn crossings(&self, other: &Path) -> Vec<(i64, i64)> {
+        let mut crossings = Vec::new();
+        for a in self.segments.iter() {
+            for b in other.segments.iter() {
+                crossings.extend(a.intersection(b).points());
+            }
+        }
+
+        crossings.sort_unstable();
+        crossings.dedup();
+        crossings
+    }
+}
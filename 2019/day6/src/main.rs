@@ -1,10 +1,15 @@
-use std::collections::{BTreeMap, BTreeSet, VecDeque};
+mod dot;
+mod tree;
+
 use std::convert::{TryFrom, TryInto};
 
 use anyhow::{format_err, Error, Result};
 use log::{debug, info};
 
-type Orbits = BTreeMap<String, OrbitInfo>;
+use dot::Dot;
+use tree::Tree;
+
+type Orbits = Tree<String>;
 
 #[derive(Debug, PartialEq, Eq)]
 struct Orbit {
@@ -12,27 +17,6 @@ struct Orbit {
     planet: String,
 }
 
-#[derive(Debug)]
-struct OrbitInfo {
-    distance_from_center: usize,
-    total_distances: usize,
-    planets: BTreeSet<String>,
-}
-
-impl OrbitInfo {
-    fn new() -> Self {
-        OrbitInfo {
-            distance_from_center: 0,
-            total_distances: 0,
-            planets: BTreeSet::new(),
-        }
-    }
-
-    fn add_planet(&mut self, planet: &str) {
-        self.planets.insert(planet.to_string());
-    }
-}
-
 impl TryFrom<&str> for Orbit {
     type Error = Error;
 
@@ -58,21 +42,13 @@ impl TryFrom<&str> for Orbit {
 }
 
 fn string_to_orbits(input: &str) -> Result<Orbits> {
-    let mut orbits = BTreeMap::new();
+    let mut pairs = Vec::new();
     for line in input.trim().split("\n") {
         let orbit: Orbit = line.try_into()?;
-
-        orbits
-            .entry(orbit.center_of_mass.clone())
-            .or_insert(OrbitInfo::new())
-            .add_planet(&orbit.planet);
-
-        orbits
-            .entry(orbit.planet.clone())
-            .or_insert(OrbitInfo::new())
-            .add_planet(&orbit.center_of_mass);
+        pairs.push((orbit.center_of_mass, orbit.planet));
     }
-    Ok(orbits)
+
+    Tree::from_pairs(&pairs)
 }
 
 fn read_input(filename: &str) -> Result<Orbits> {
@@ -81,66 +57,42 @@ fn read_input(filename: &str) -> Result<Orbits> {
     string_to_orbits(&data)
 }
 
-fn shortest_path_length(orbits: &Orbits, start: &str, end: &str) -> Result<usize> {
-    let mut to_check = VecDeque::new();
-    to_check.push_back(start);
-
-    let mut counts = BTreeMap::new();
-
-    for planet in orbits.keys() {
-        counts.insert(planet.to_string(), std::usize::MAX);
-    }
-
-    counts.insert(start.to_string(), 0);
+fn main() -> Result<()> {
+    env_logger::from_env(env_logger::Env::default().default_filter_or("info")).init();
 
-    let mut visited = BTreeSet::new();
+    let orbits = read_input("input.txt")?;
 
-    while !to_check.is_empty() {
-        let next = to_check.pop_front().unwrap();
-        debug!("Checking {}", next);
+    let you = "YOU".to_string();
+    let santa = "SAN".to_string();
 
-        if visited.contains(next) {
-            debug!("Already visted {}", next);
-            continue;
+    if std::env::args().any(|arg| arg == "--dot") {
+        let mut dot = Dot::new("orbits");
+        for (center_of_mass, planet) in orbits.edges() {
+            dot.edge(center_of_mass, planet, None);
         }
-
-        visited.insert(next);
-
-        let count = counts
-            .get(next)
-            .ok_or(format_err!("Count not found for {}", next))?;
-        let next_hop = count + 1;
-
-        let info = orbits
-            .get(next)
-            .ok_or(format_err!("Orbit info not found for {}", next))?;
-
-        for planet in info.planets.iter() {
-            let next_planet_count = *counts
-                .get(planet)
-                .ok_or(format_err!("Comparison count not found for {}", planet))?;
-            if next_hop < next_planet_count {
-                counts.insert(planet.to_string(), next_hop);
-                to_check.push_back(planet);
-            }
+        let path = orbits.path(&you, &santa)?;
+        for node in path.iter() {
+            dot.node(node);
         }
-    }
+        dot.label(&you, "You");
+        dot.label(&santa, "Santa");
+        dot.highlight_path(&path);
 
-    Ok(*counts
-        .get(end)
-        .ok_or(format_err!("End count not found for {}", end))?)
-}
-
-fn main() -> Result<()> {
-    env_logger::from_env(env_logger::Env::default().default_filter_or("info")).init();
-
-    let orbits = read_input("input.txt")?;
+        print!("{}", dot);
+        return Ok(());
+    }
 
     debug!("Orbits: {:?}", orbits);
 
+    info!("Total orbits: {}", orbits.total_depth());
+    info!("Shortest path: {}", orbits.distance(&you, &santa)?);
+
+    // Transfers move between the objects YOU and SAN orbit, one level below their common
+    // ancestor on each side.
+    let common = orbits.lowest_common_ancestor(&you, &santa)?;
     info!(
-        "Shortest path: {}",
-        shortest_path_length(&orbits, "YOU", "SAN")?
+        "Orbital transfers: {}",
+        orbits.depth(&you)? + orbits.depth(&santa)? - 2 * orbits.depth(common)? - 2
     );
 
     Ok(())
@@ -0,0 +1,239 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Debug;
+
+use anyhow::{format_err, Result};
+
+/// A rooted tree with every node's depth and a binary lifting table precomputed, so ancestor
+/// and distance queries take logarithmic time.
+#[derive(Debug)]
+pub struct Tree<N: Ord + Clone + Debug> {
+    ids: BTreeMap<N, usize>,
+    nodes: Vec<N>,
+    depths: Vec<usize>,
+    /// `ancestors[k][i]` is the ancestor 2^k levels above node `i`, or the root if that is
+    /// further up than the root.
+    ancestors: Vec<Vec<usize>>,
+    root: usize,
+}
+
+impl<N: Ord + Clone + Debug> Tree<N> {
+    /// Builds a tree from (parent, child) pairs. Fails unless the pairs connect every node
+    /// below exactly one root with no node having two parents.
+    pub fn from_pairs(pairs: &[(N, N)]) -> Result<Self> {
+        let mut ids = BTreeMap::new();
+        let mut nodes = Vec::new();
+        let mut id_of = |node: &N| -> usize {
+            *ids.entry(node.clone()).or_insert_with(|| {
+                nodes.push(node.clone());
+                nodes.len() - 1
+            })
+        };
+
+        let mut links = Vec::new();
+        for (parent, child) in pairs.iter() {
+            links.push((id_of(parent), id_of(child)));
+        }
+
+        let mut parents = vec![None; nodes.len()];
+        let mut children = vec![Vec::new(); nodes.len()];
+        for (parent, child) in links {
+            if let Some(existing) = parents[child] {
+                return Err(format_err!(
+                    "{:?} has two parents, {:?} and {:?}",
+                    nodes[child],
+                    nodes[existing],
+                    nodes[parent]
+                ));
+            }
+
+            parents[child] = Some(parent);
+            children[parent].push(child);
+        }
+
+        let roots: Vec<usize> = (0..nodes.len()).filter(|i| parents[*i].is_none()).collect();
+        let root = match roots.as_slice() {
+            [root] => *root,
+            [] => return Err(format_err!("Every node has a parent, so there is no root")),
+            _ => {
+                return Err(format_err!(
+                    "Found {} roots, including {:?} and {:?}",
+                    roots.len(),
+                    nodes[roots[0]],
+                    nodes[roots[1]]
+                ))
+            }
+        };
+
+        let mut depths = vec![usize::MAX; nodes.len()];
+        depths[root] = 0;
+        let mut to_visit = VecDeque::new();
+        to_visit.push_back(root);
+        while let Some(node) = to_visit.pop_front() {
+            for child in children[node].iter() {
+                depths[*child] = depths[node] + 1;
+                to_visit.push_back(*child);
+            }
+        }
+
+        if let Some(unreached) = depths.iter().position(|depth| *depth == usize::MAX) {
+            return Err(format_err!(
+                "{:?} is on a cycle and not below {:?}",
+                nodes[unreached],
+                nodes[root]
+            ));
+        }
+
+        let mut ancestors = vec![parents
+            .iter()
+            .map(|parent| parent.unwrap_or(root))
+            .collect::<Vec<usize>>()];
+        while 1 << ancestors.len() < nodes.len() {
+            let previous = &ancestors[ancestors.len() - 1];
+            let next = previous.iter().map(|middle| previous[*middle]).collect();
+            ancestors.push(next);
+        }
+
+        Ok(Tree {
+            ids,
+            nodes,
+            depths,
+            ancestors,
+            root,
+        })
+    }
+
+    fn id(&self, node: &N) -> Result<usize> {
+        self.ids
+            .get(node)
+            .copied()
+            .ok_or_else(|| format_err!("{:?} is not in the tree", node))
+    }
+
+    pub fn depth(&self, node: &N) -> Result<usize> {
+        Ok(self.depths[self.id(node)?])
+    }
+
+    /// The sum of every node's depth, i.e. the number of direct and indirect parent links.
+    pub fn total_depth(&self) -> usize {
+        self.depths.iter().sum()
+    }
+
+    /// Walks `levels` steps up from node `id`, stopping at the root.
+    fn lift(&self, mut id: usize, levels: usize) -> usize {
+        for (k, table) in self.ancestors.iter().enumerate() {
+            if levels & (1 << k) != 0 {
+                id = table[id];
+            }
+        }
+
+        id
+    }
+
+    fn lca_id(&self, a: usize, b: usize) -> usize {
+        let (mut a, mut b) = if self.depths[a] >= self.depths[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        a = self.lift(a, self.depths[a] - self.depths[b]);
+
+        if a == b {
+            return a;
+        }
+
+        for table in self.ancestors.iter().rev() {
+            if table[a] != table[b] {
+                a = table[a];
+                b = table[b];
+            }
+        }
+
+        self.ancestors[0][a]
+    }
+
+    /// The deepest node that is an ancestor of both `a` and `b`, counting each as its own
+    /// ancestor.
+    pub fn lowest_common_ancestor(&self, a: &N, b: &N) -> Result<&N> {
+        Ok(&self.nodes[self.lca_id(self.id(a)?, self.id(b)?)])
+    }
+
+    /// Every (parent, child) link in the tree.
+    pub fn edges(&self) -> impl Iterator<Item = (&N, &N)> {
+        (0..self.nodes.len())
+            .filter(move |id| *id != self.root)
+            .map(move |id| (&self.nodes[self.ancestors[0][id]], &self.nodes[id]))
+    }
+
+    /// The nodes on the path from `a` to `b`, inclusive of both.
+    pub fn path(&self, a: &N, b: &N) -> Result<Vec<&N>> {
+        let (mut a, mut b) = (self.id(a)?, self.id(b)?);
+        let lca = self.lca_id(a, b);
+
+        let mut up = vec![&self.nodes[a]];
+        while a != lca {
+            a = self.ancestors[0][a];
+            up.push(&self.nodes[a]);
+        }
+
+        let mut down = Vec::new();
+        while b != lca {
+            down.push(&self.nodes[b]);
+            b = self.ancestors[0][b];
+        }
+
+        up.extend(down.into_iter().rev());
+        Ok(up)
+    }
+
+    /// The number of edges on the path between `a` and `b`.
+    pub fn distance(&self, a: &N, b: &N) -> Result<usize> {
+        let (a, b) = (self.id(a)?, self.id(b)?);
+        let lca = self.lca_id(a, b);
+
+        Ok(self.depths[a] + self.depths[b] - 2 * self.depths[lca])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example() -> Result<()> {
+        let pairs: Vec<(String, String)> = [
+            "COM)B", "B)C", "C)D", "D)E", "E)F", "B)G", "G)H", "D)I", "E)J", "J)K", "K)L", "K)YOU",
+            "I)SAN",
+        ]
+        .iter()
+        .map(|pair| {
+            let (parent, child) = pair.split_once(')').unwrap();
+            (parent.to_string(), child.to_string())
+        })
+        .collect();
+        let tree = Tree::from_pairs(&pairs)?;
+
+        assert_eq!(tree.depth(&"COM".to_string())?, 0);
+        assert_eq!(tree.total_depth(), 54);
+        assert_eq!(
+            tree.lowest_common_ancestor(&"YOU".to_string(), &"SAN".to_string())?,
+            "D"
+        );
+        assert_eq!(tree.distance(&"YOU".to_string(), &"SAN".to_string())?, 6);
+        assert_eq!(tree.distance(&"H".to_string(), &"COM".to_string())?, 3);
+        assert_eq!(
+            tree.path(&"YOU".to_string(), &"SAN".to_string())?,
+            vec!["YOU", "K", "J", "E", "D", "I", "SAN"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_bad_trees() {
+        let two_roots = vec![(1, 2), (3, 4)];
+        assert!(Tree::from_pairs(&two_roots).is_err());
+
+        let cycle = vec![(0, 1), (2, 3), (3, 2)];
+        assert!(Tree::from_pairs(&cycle).is_err());
+    }
+}
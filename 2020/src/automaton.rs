@@ -0,0 +1,57 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// A position in a cellular automaton.
+pub trait Cell: Clone + Eq + Hash {
+    fn neighbors(&self) -> Vec<Self>;
+}
+
+/// A Life-like rule: an inactive cell becomes active when its count of active neighbors is
+/// in `birth`, and an active cell stays active when its count is in `survival`.
+#[derive(Clone, Debug)]
+pub struct Rule {
+    birth: Vec<usize>,
+    survival: Vec<usize>,
+}
+
+impl Rule {
+    pub fn new(birth: &[usize], survival: &[usize]) -> Self {
+        Self {
+            birth: birth.to_vec(),
+            survival: survival.to_vec(),
+        }
+    }
+
+    /// B3/S23, as used by Conway's Game of Life.
+    pub fn conway() -> Self {
+        Self::new(&[3], &[2, 3])
+    }
+
+    pub fn next_state(&self, active: bool, active_neighbors: usize) -> bool {
+        if active {
+            self.survival.contains(&active_neighbors)
+        } else {
+            self.birth.contains(&active_neighbors)
+        }
+    }
+}
+
+/// Advances the automaton one generation.
+///
+/// Only cells with at least one active neighbor are considered, so a rule that survives or
+/// births on zero neighbors won't behave as expected.
+pub fn step<C: Cell>(active: &HashSet<C>, rule: &Rule) -> HashSet<C> {
+    let mut counts: HashMap<C, usize> = HashMap::new();
+
+    for cell in active.iter() {
+        for neighbor in cell.neighbors() {
+            *counts.entry(neighbor).or_insert(0) += 1;
+        }
+    }
+
+    counts
+        .into_iter()
+        .filter(|(cell, count)| rule.next_state(active.contains(cell), *count))
+        .map(|(cell, _)| cell)
+        .collect()
+}
@@ -1,9 +1,20 @@
-use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::collections::{BTreeMap, BTreeSet};
 
 use anyhow::{format_err, Result};
+use num_bigint::BigUint;
 use structopt::StructOpt;
 
-use aoc_2020::{read_lines, Args};
+use aoc_2020::dag::Dag;
+use aoc_2020::read_lines;
+
+#[derive(StructOpt)]
+struct Args {
+    filename: String,
+
+    /// Print the adapters as a Graphviz graph, highlighting the chain that uses all of them
+    #[structopt(long)]
+    dot: bool,
+}
 
 fn part1(adapters: &[i64]) -> i64 {
     let mut differences = BTreeMap::new();
@@ -11,7 +22,7 @@ fn part1(adapters: &[i64]) -> i64 {
     let mut current_jolt = 0;
     for adapter in adapters.iter() {
         let difference = *adapter - current_jolt;
-        if difference < 0 || difference > 3 {
+        if !(0..=3).contains(&difference) {
             break;
         }
 
@@ -22,76 +33,34 @@ fn part1(adapters: &[i64]) -> i64 {
     differences[&3] * differences[&1]
 }
 
-fn make_graph(adapters: &[i64], target: i64) -> Result<BTreeMap<i64, BTreeSet<i64>>> {
-    let mut graph = BTreeMap::new();
+fn make_graph(adapters: &[i64], target: i64) -> Dag<i64> {
+    let mut graph = Dag::new();
 
     let mut adapters_set: BTreeSet<i64> = adapters.iter().cloned().collect();
+    adapters_set.insert(0);
     adapters_set.insert(target);
 
-    let mut to_find = VecDeque::new();
-    to_find.push_back(0);
-    let mut visited = BTreeSet::new();
-
-    while !to_find.is_empty() {
-        let start = to_find
-            .pop_front()
-            .ok_or_else(|| format_err!("somehow got an empty find queue"))?;
-
-        if visited.contains(&start) {
-            continue;
-        }
-
-        visited.insert(start);
-
+    for start in adapters_set.iter() {
+        graph.add_node(*start);
         for i in 1..=3 {
             let possible = start + i;
             if adapters_set.contains(&possible) {
-                graph
-                    .entry(start)
-                    .or_insert(BTreeSet::new())
-                    .insert(possible);
-                to_find.push_back(possible);
+                graph.add_edge(*start, possible);
             }
         }
     }
 
-    Ok(graph)
-}
-
-fn count_paths(
-    graph: &BTreeMap<i64, BTreeSet<i64>>,
-    memo: &mut BTreeMap<i64, i64>,
-    start: i64,
-    target: i64,
-) -> i64 {
-    if start == target {
-        return 1;
-    }
-
-    let mut total = 0;
-    for child in graph[&start].iter() {
-        total += match memo.get(&child) {
-            Some(value) => *value,
-            None => {
-                let value = count_paths(graph, memo, *child, target);
-                memo.insert(*child, value);
-                value
-            }
-        };
-    }
-
-    total
+    graph
 }
 
-fn part2(adapters: &[i64]) -> Result<i64> {
+fn part2(adapters: &[i64]) -> Result<BigUint> {
     let target = adapters
         .last()
         .ok_or_else(|| format_err!("must have at least one adapter"))?;
 
-    let graph = make_graph(adapters, *target)?;
-    let mut memo = BTreeMap::new();
+    let graph = make_graph(adapters, *target);
 
-    Ok(count_paths(&graph, &mut memo, 0, *target))
+    graph.count_paths(&0, target)
 }
 
 fn main() -> Result<()> {
@@ -108,6 +77,15 @@ fn main() -> Result<()> {
 
     adapters.push(highest_adapter);
 
+    if args.dot {
+        let mut dot = make_graph(&adapters, highest_adapter).to_dot("adapters", false);
+        let chain: Vec<i64> = std::iter::once(0).chain(adapters.iter().cloned()).collect();
+        dot.highlight_path(&chain);
+
+        print!("{}", dot);
+        return Ok(());
+    }
+
     println!("Part 1: {}", part1(&adapters));
     println!("Part 2: {}", part2(&adapters)?);
 
@@ -1,13 +1,13 @@
-use std::collections::HashMap;
+use std::collections::HashSet;
 use std::convert::{TryFrom, TryInto};
-use std::str::FromStr;
 
-use anyhow::{format_err, Result};
+use anyhow::Result;
 //use maplit::hashmap;
 use structopt::StructOpt;
 use thiserror::Error;
 
-use aoc_2020::{read_lines, Args};
+use aoc_2020::automaton::{self, Cell, Rule};
+use aoc_2020::Args;
 
 #[derive(Clone, Debug)]
 enum Tile {
@@ -49,7 +49,7 @@ struct Vec3 {
     z: i64,
 }
 
-impl Vec3 {
+impl Cell for Vec3 {
     fn neighbors(&self) -> Vec<Vec3> {
         let mut points = Vec::new();
 
@@ -89,132 +89,54 @@ impl From<&Vec3> for Vec3 {
     }
 }
 
-struct NeighborStates {
-    active: usize,
-    inactive: usize,
-}
-
-impl NeighborStates {
-    fn new() -> Self {
-        Self {
-            active: 0,
-            inactive: 0,
-        }
-    }
-}
-
 #[derive(Debug)]
 struct Map3 {
-    min_x: i64,
-    min_y: i64,
-    min_z: i64,
-    max_x: i64,
-    max_y: i64,
-    max_z: i64,
-
-    tiles: HashMap<Vec3, Tile>,
+    active: HashSet<Vec3>,
 }
 
 impl Map3 {
     fn new() -> Self {
         Self {
-            min_x: 0,
-            min_y: 0,
-            min_z: 0,
-            max_x: 0,
-            max_y: 0,
-            max_z: 0,
-            tiles: HashMap::new(),
+            active: HashSet::new(),
         }
     }
 
     fn set_tile<T: Into<Vec3>>(&mut self, point: T, tile: Tile) {
-        let point: Vec3 = point.into();
-
-        self.min_x = std::cmp::min(point.x, self.min_x);
-        self.min_y = std::cmp::min(point.y, self.min_y);
-        self.min_z = std::cmp::min(point.z, self.min_z);
-
-        self.max_x = std::cmp::max(point.x, self.max_x);
-        self.max_y = std::cmp::max(point.y, self.max_y);
-        self.max_z = std::cmp::max(point.z, self.max_z);
-
-        self.tiles.insert(point, tile);
-    }
-
-    fn get_tile<T: Into<Vec3>>(&self, point: T) -> Tile {
-        self.tiles
-            .get(&point.into())
-            .cloned()
-            .unwrap_or(Tile::Inactive)
-    }
-
-    fn tile_neighbor_states<T: Into<Vec3>>(&self, point: T) -> NeighborStates {
-        let mut states = NeighborStates::new();
-
-        let point = point.into();
-        for neighbor in point.neighbors() {
-            match self.get_tile(neighbor) {
-                Tile::Active => states.active += 1,
-                Tile::Inactive => states.inactive += 1,
-            }
-        }
-
-        states
+        match tile {
+            Tile::Active => self.active.insert(point.into()),
+            Tile::Inactive => self.active.remove(&point.into()),
+        };
     }
 
     fn tick(&self) -> Self {
-        let mut new_map = Self::new();
-
-        for z in self.min_z - 1..=self.max_z + 1 {
-            for y in self.min_y - 1..=self.max_y + 1 {
-                for x in self.min_x - 1..=self.max_x + 1 {
-                    let position: Vec3 = (x, y, z).into();
-
-                    match self.get_tile(position.clone()) {
-                        Tile::Active => {
-                            let states = self.tile_neighbor_states(position.clone());
-                            if states.active == 2 || states.active == 3 {
-                                new_map.set_tile(position, Tile::Active);
-                            } else {
-                                new_map.set_tile(position, Tile::Inactive);
-                            }
-                        }
-                        Tile::Inactive => {
-                            let states = self.tile_neighbor_states(position.clone());
-                            if states.active == 3 {
-                                new_map.set_tile(position, Tile::Active);
-                            } else {
-                                new_map.set_tile(position, Tile::Inactive);
-                            }
-                        }
-                    }
-                }
-            }
+        Self {
+            active: automaton::step(&self.active, &Rule::conway()),
         }
-
-        new_map
     }
 
     fn count_active(&self) -> usize {
-        let mut count = 0;
-
-        for state in self.tiles.values() {
-            if let Tile::Active = state {
-                count += 1;
-            }
-        }
-
-        count
+        self.active.len()
     }
 
     fn print(&self) {
-        for z in self.min_z..=self.max_z {
+        let min_x = self.active.iter().map(|p| p.x).min().unwrap_or(0);
+        let min_y = self.active.iter().map(|p| p.y).min().unwrap_or(0);
+        let min_z = self.active.iter().map(|p| p.z).min().unwrap_or(0);
+        let max_x = self.active.iter().map(|p| p.x).max().unwrap_or(0);
+        let max_y = self.active.iter().map(|p| p.y).max().unwrap_or(0);
+        let max_z = self.active.iter().map(|p| p.z).max().unwrap_or(0);
+
+        for z in min_z..=max_z {
             println!("z={}", z);
-            for y in self.min_y..=self.max_y {
+            for y in min_y..=max_y {
                 let mut line: Vec<char> = Vec::new();
-                for x in self.min_x..=self.max_x {
-                    line.push(self.get_tile((x, y, z)).into());
+                for x in min_x..=max_x {
+                    let tile = if self.active.contains(&(x, y, z).into()) {
+                        Tile::Active
+                    } else {
+                        Tile::Inactive
+                    };
+                    line.push(tile.into());
                 }
                 println!("{}", line.iter().collect::<String>());
             }
@@ -243,7 +165,7 @@ fn main() -> Result<()> {
 
     //println!("start");
     //map.print();
-    for i in 0..6 {
+    for _ in 0..6 {
         map = map.tick();
 
         //println!("");
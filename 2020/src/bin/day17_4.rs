@@ -1,13 +1,13 @@
-use std::collections::HashMap;
+use std::collections::HashSet;
 use std::convert::{TryFrom, TryInto};
-use std::str::FromStr;
 
-use anyhow::{format_err, Result};
+use anyhow::Result;
 //use maplit::hashmap;
 use structopt::StructOpt;
 use thiserror::Error;
 
-use aoc_2020::{read_lines, Args};
+use aoc_2020::automaton::{self, Cell, Rule};
+use aoc_2020::Args;
 
 #[derive(Clone, Debug)]
 enum Tile {
@@ -50,7 +50,7 @@ struct Vec4 {
     z: i64,
 }
 
-impl Vec4 {
+impl Cell for Vec4 {
     fn neighbors(&self) -> Vec<Vec4> {
         let mut points = Vec::new();
 
@@ -102,147 +102,34 @@ impl From<&Vec4> for Vec4 {
     }
 }
 
-struct NeighborStates {
-    active: usize,
-    inactive: usize,
-}
-
-impl NeighborStates {
-    fn new() -> Self {
-        Self {
-            active: 0,
-            inactive: 0,
-        }
-    }
-}
-
 #[derive(Debug)]
 struct Map4 {
-    min_w: i64,
-    min_x: i64,
-    min_y: i64,
-    min_z: i64,
-    max_w: i64,
-    max_x: i64,
-    max_y: i64,
-    max_z: i64,
-
-    tiles: HashMap<Vec4, Tile>,
+    active: HashSet<Vec4>,
 }
 
 impl Map4 {
     fn new() -> Self {
         Self {
-            min_w: 0,
-            min_x: 0,
-            min_y: 0,
-            min_z: 0,
-            max_w: 0,
-            max_x: 0,
-            max_y: 0,
-            max_z: 0,
-            tiles: HashMap::new(),
+            active: HashSet::new(),
         }
     }
 
     fn set_tile<T: Into<Vec4>>(&mut self, point: T, tile: Tile) {
-        let point: Vec4 = point.into();
-
-        self.min_w = std::cmp::min(point.w, self.min_w);
-        self.min_x = std::cmp::min(point.x, self.min_x);
-        self.min_y = std::cmp::min(point.y, self.min_y);
-        self.min_z = std::cmp::min(point.z, self.min_z);
-
-        self.max_w = std::cmp::max(point.w, self.max_w);
-        self.max_x = std::cmp::max(point.x, self.max_x);
-        self.max_y = std::cmp::max(point.y, self.max_y);
-        self.max_z = std::cmp::max(point.z, self.max_z);
-
-        self.tiles.insert(point, tile);
-    }
-
-    fn get_tile<T: Into<Vec4>>(&self, point: T) -> Tile {
-        self.tiles
-            .get(&point.into())
-            .cloned()
-            .unwrap_or(Tile::Inactive)
-    }
-
-    fn tile_neighbor_states<T: Into<Vec4>>(&self, point: T) -> NeighborStates {
-        let mut states = NeighborStates::new();
-
-        let point = point.into();
-        for neighbor in point.neighbors() {
-            match self.get_tile(neighbor) {
-                Tile::Active => states.active += 1,
-                Tile::Inactive => states.inactive += 1,
-            }
-        }
-
-        states
+        match tile {
+            Tile::Active => self.active.insert(point.into()),
+            Tile::Inactive => self.active.remove(&point.into()),
+        };
     }
 
     fn tick(&self) -> Self {
-        let mut new_map = Self::new();
-
-        for z in self.min_z - 1..=self.max_z + 1 {
-            for y in self.min_y - 1..=self.max_y + 1 {
-                for x in self.min_x - 1..=self.max_x + 1 {
-                    for w in self.min_w - 1..=self.max_w + 1 {
-                        let position: Vec4 = (w, x, y, z).into();
-
-                        match self.get_tile(position.clone()) {
-                            Tile::Active => {
-                                let states = self.tile_neighbor_states(position.clone());
-                                if states.active == 2 || states.active == 3 {
-                                    new_map.set_tile(position, Tile::Active);
-                                } else {
-                                    new_map.set_tile(position, Tile::Inactive);
-                                }
-                            }
-                            Tile::Inactive => {
-                                let states = self.tile_neighbor_states(position.clone());
-                                if states.active == 3 {
-                                    new_map.set_tile(position, Tile::Active);
-                                } else {
-                                    new_map.set_tile(position, Tile::Inactive);
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+        Self {
+            active: automaton::step(&self.active, &Rule::conway()),
         }
-
-        new_map
     }
 
     fn count_active(&self) -> usize {
-        let mut count = 0;
-
-        for state in self.tiles.values() {
-            if let Tile::Active = state {
-                count += 1;
-            }
-        }
-
-        count
-    }
-
-    /*
-    fn print(&self) {
-        for z in self.min_z..=self.max_z {
-            println!("z={}", z);
-            for y in self.min_y..=self.max_y {
-                let mut line: Vec<char> = Vec::new();
-                for x in self.min_x..=self.max_x {
-                    line.push(self.get_tile((x, y, z)).into());
-                }
-                println!("{}", line.iter().collect::<String>());
-            }
-        }
+        self.active.len()
     }
-    */
 }
 
 fn main() -> Result<()> {
@@ -266,7 +153,7 @@ fn main() -> Result<()> {
 
     //println!("start");
     //map.print();
-    for i in 0..6 {
+    for _ in 0..6 {
         map = map.tick();
 
         //println!("");
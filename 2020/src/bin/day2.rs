@@ -2,9 +2,9 @@ use std::str::FromStr;
 
 use anyhow::Result;
 use structopt::StructOpt;
-use thiserror::Error;
 
-use aoc_2020::{read_lines, Args};
+use aoc_2020::template::{Template, TemplateError};
+use aoc_2020::{lines_iter, Args};
 
 #[derive(Debug)]
 struct Policy {
@@ -13,64 +13,12 @@ struct Policy {
     letter: char,
 }
 
-#[derive(Debug, Error)]
-enum PolicyError {
-    #[error("general error: {0}")]
-    General(String),
-}
-
-impl FromStr for Policy {
-    type Err = PolicyError;
-
-    fn from_str(line: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<&str> = line.split("-").collect();
-        let lower_count: usize = parts
-            .get(0)
-            .ok_or_else(|| PolicyError::General(format!("{} missing lower", line)))?
-            .parse()
-            .map_err(|_| PolicyError::General("can't parse".to_string()))?;
-        let parts: Vec<&str> = parts
-            .get(1)
-            .ok_or_else(|| PolicyError::General(format!("{} missing rest", line)))?
-            .split(" ")
-            .collect();
-        let higher_count: usize = parts
-            .get(0)
-            .ok_or_else(|| PolicyError::General(format!("{} missing higher", line)))?
-            .parse()
-            .map_err(|_| PolicyError::General("can't parse".to_string()))?;
-        let parts: Vec<&str> = parts
-            .get(1)
-            .ok_or_else(|| PolicyError::General(format!("{} missing rest", line)))?
-            .split(":")
-            .collect();
-        let letter: char = parts
-            .get(0)
-            .ok_or_else(|| PolicyError::General(format!("{} missing letter", line)))?
-            .parse()
-            .map_err(|_| PolicyError::General("can't parse".to_string()))?;
-        Ok(Self {
-            lower_count,
-            higher_count,
-            letter,
-        })
-    }
-}
-
 #[derive(Debug)]
 struct Password {
     policy: Policy,
     password: String,
 }
 
-#[derive(Debug, Error)]
-enum PasswordError {
-    #[error("policy error")]
-    Policy(#[from] PolicyError),
-    #[error("general error: {0}")]
-    General(String),
-}
-
 fn str_has_char_at(haystack: &str, needle: char, index: usize) -> bool {
     if let Some(letter) = haystack.chars().nth(index - 1) {
         if letter == needle {
@@ -82,18 +30,20 @@ fn str_has_char_at(haystack: &str, needle: char, index: usize) -> bool {
 }
 
 impl FromStr for Password {
-    type Err = PasswordError;
+    type Err = TemplateError;
 
     fn from_str(line: &str) -> Result<Self, Self::Err> {
-        let policy = Policy::from_str(line)?;
-
-        let parts: Vec<&str> = line.split(" ").collect();
-        let password = parts
-            .get(2)
-            .ok_or_else(|| PasswordError::General(format!("{} missing password", line)))?
-            .to_string();
+        let captures =
+            Template::new("{lower_count}-{higher_count} {letter}: {password}")?.captures(line)?;
 
-        Ok(Self { policy, password })
+        Ok(Self {
+            policy: Policy {
+                lower_count: captures.parse("lower_count")?,
+                higher_count: captures.parse("higher_count")?,
+                letter: captures.parse("letter")?,
+            },
+            password: captures.get("password")?.to_string(),
+        })
     }
 }
 
@@ -111,11 +61,9 @@ impl Password {
 fn main() -> Result<()> {
     let args = Args::from_args();
 
-    let passwords: Vec<Password> = read_lines(&args.filename)?;
-
     let mut count = 0;
-    for password in passwords.into_iter() {
-        if password.passes() {
+    for password in lines_iter::<Password>(&args.filename)? {
+        if password?.passes() {
             count += 1;
         }
     }
@@ -1,10 +1,11 @@
 use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
 
 use anyhow::{format_err, Result};
 use maplit::btreeset;
 use structopt::StructOpt;
 
-use aoc_2020::Args;
+use aoc_2020::{read_records, Args};
 
 fn is_valid_byr(value: &str) -> bool {
     if value.len() != 4 {
@@ -236,23 +237,12 @@ impl Passport {
     }
 }
 
-fn main() -> Result<()> {
-    let args = Args::from_args();
-
-    let mut passports = Vec::new();
-
-    let contents = std::fs::read_to_string(args.filename)?;
-
-    let mut current_passport = Passport::new();
-    for line in contents.split("\n") {
-        if line.trim().is_empty() {
-            passports.push(current_passport);
-            current_passport = Passport::new();
-
-            continue;
-        }
+impl FromStr for Passport {
+    type Err = anyhow::Error;
 
-        for token in line.split_whitespace() {
+    fn from_str(record: &str) -> Result<Self> {
+        let mut passport = Passport::new();
+        for token in record.split_whitespace() {
             let parts: Vec<&str> = token.split(":").collect();
             let field = parts
                 .get(0)
@@ -261,9 +251,17 @@ fn main() -> Result<()> {
                 .get(1)
                 .ok_or_else(|| format_err!("token \"{}\" missing value", token))?;
 
-            current_passport.add_field(field, value);
+            passport.add_field(field, value);
         }
+
+        Ok(passport)
     }
+}
+
+fn main() -> Result<()> {
+    let args = Args::from_args();
+
+    let passports: Vec<Passport> = read_records(&args.filename)?;
 
     let mut valid_passport_count = 0;
     for passport in passports.into_iter() {
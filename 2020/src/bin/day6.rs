@@ -1,9 +1,11 @@
 use std::collections::{BTreeMap, BTreeSet};
+use std::convert::Infallible;
+use std::str::FromStr;
 
 use anyhow::Result;
 use structopt::StructOpt;
 
-use aoc_2020::Args;
+use aoc_2020::{read_records, Args};
 
 #[derive(Debug)]
 struct Group {
@@ -43,28 +45,23 @@ impl Group {
     }
 }
 
-fn main() -> Result<()> {
-    let args = Args::from_args();
-
-    let contents = std::fs::read_to_string(args.filename)?;
-
-    let mut groups = Vec::new();
-    let mut current_group = Group::new();
-
-    for line in contents.split("\n") {
-        let line = line.trim();
+impl FromStr for Group {
+    type Err = Infallible;
 
-        if line.is_empty() {
-            groups.push(current_group);
-            current_group = Group::new();
-
-            continue;
+    fn from_str(record: &str) -> Result<Self, Self::Err> {
+        let mut group = Group::new();
+        for line in record.lines() {
+            group.add_questions(line.trim().chars().collect());
         }
 
-        current_group.add_questions(line.chars().collect());
+        Ok(group)
     }
+}
+
+fn main() -> Result<()> {
+    let args = Args::from_args();
 
-    groups.push(current_group);
+    let groups: Vec<Group> = read_records(&args.filename)?;
 
     let mut total = 0;
     for group in groups.into_iter() {
@@ -1,11 +1,9 @@
-use std::collections::{BTreeMap, BTreeSet, VecDeque};
-
 use anyhow::{format_err, Result};
 use structopt::StructOpt;
 
-use aoc_2020::Args;
+use aoc_2020::dag::Dag;
 
-type BagMap = BTreeMap<String, Vec<BagCount>>;
+type BagMap = Dag<String>;
 
 fn name_from_string(input: &str) -> Result<BagCount> {
     let parts: Vec<&str> = input.split(" ").collect();
@@ -14,7 +12,7 @@ fn name_from_string(input: &str) -> Result<BagCount> {
             let name = format!(
                 "{} {}",
                 parts
-                    .get(0)
+                    .first()
                     .ok_or_else(|| format_err!("\"{}\" missing adjective"))?,
                 parts
                     .get(1)
@@ -25,7 +23,7 @@ fn name_from_string(input: &str) -> Result<BagCount> {
         }
         4 => {
             let count: usize = parts
-                .get(0)
+                .first()
                 .ok_or_else(|| format_err!("\"{}\" missing count"))?
                 .parse()?;
 
@@ -51,10 +49,25 @@ struct BagCount {
     name: String,
 }
 
-fn build_map(filename: String) -> Result<BagMap> {
+#[derive(StructOpt)]
+struct Args {
+    filename: String,
+
+    /// The bag to ask about
+    #[structopt(long, default_value = "shiny gold")]
+    color: String,
+
+    /// Print the rules as a Graphviz graph, highlighting the bags that can hold the color
+    #[structopt(long)]
+    dot: bool,
+}
+
+/// Each rule becomes an edge from the outer bag to every bag it must hold, weighted by how
+/// many it holds.
+fn build_map(filename: &str) -> Result<BagMap> {
     let contents = std::fs::read_to_string(filename)?;
 
-    let mut bags = BTreeMap::new();
+    let mut bags = Dag::new();
     for line in contents.split("\n") {
         let line = line.trim();
 
@@ -66,9 +79,10 @@ fn build_map(filename: String) -> Result<BagMap> {
 
         let outer = name_from_string(
             parts
-                .get(0)
+                .first()
                 .ok_or_else(|| format_err!("\"{}\" missing outer bag name", line))?,
         )?;
+        bags.add_node(outer.name.clone());
 
         let inner_parts: Vec<&str> = parts
             .get(1)
@@ -76,80 +90,42 @@ fn build_map(filename: String) -> Result<BagMap> {
             .split(", ")
             .collect();
 
-        let mut inner = Vec::new();
         for inner_part in inner_parts.iter() {
-            inner.push(name_from_string(inner_part)?);
-        }
+            if inner_part.starts_with("no other") {
+                continue;
+            }
 
-        bags.insert(outer.name, inner);
+            let inner = name_from_string(inner_part)?;
+            bags.add_weighted_edge(outer.name.clone(), inner.name, inner.count as i64);
+        }
     }
 
     Ok(bags)
 }
 
-fn chain_contains_gold(bags: &BagMap, start: &str) -> Result<bool> {
-    let mut to_visit = VecDeque::new();
-
-    to_visit.push_back(start.to_string());
-
-    let mut visited = BTreeSet::new();
-
-    while !to_visit.is_empty() {
-        let next = to_visit
-            .pop_back()
-            .ok_or_else(|| format_err!("no more items to pop despite stack being non-empty"))?;
-
-        if visited.contains(&next) {
-            continue;
-        }
+fn main() -> Result<()> {
+    let args = Args::from_args();
 
-        visited.insert(next.clone());
+    let bags = build_map(&args.filename)?;
 
-        if let Some(counts) = bags.get(&next) {
-            for count in counts.iter() {
-                if count.name == "shiny gold" {
-                    return Ok(true);
+    if args.dot {
+        let mut dot = bags.to_dot("bags", true);
+        let ancestors = bags.ancestors(&args.color);
+        dot.highlight_node(&args.color);
+        for outer in ancestors.iter() {
+            for (inner, _) in bags.successors(outer) {
+                if *inner == args.color || ancestors.contains(inner) {
+                    dot.highlight_path(&[outer, inner]);
                 }
-
-                to_visit.push_back(count.name.clone());
             }
         }
-    }
-
-    Ok(false)
-}
-
-fn count_chains_containing_gold(bags: &BagMap) -> Result<usize> {
-    let mut count = 0;
-    for name in bags.keys() {
-        if chain_contains_gold(bags, name)? {
-            count += 1;
-        }
-    }
-
-    Ok(count)
-}
 
-fn sum_children(bags: &BagMap, start: &str) -> usize {
-    match bags.get(start) {
-        Some(counts) => {
-            let mut sum = 1;
-            for count in counts.iter() {
-                sum += count.count * sum_children(bags, &count.name);
-            }
-            sum
-        }
-        None => 0,
+        print!("{}", dot);
+        return Ok(());
     }
-}
-
-fn main() -> Result<()> {
-    let args = Args::from_args();
-
-    let bags = build_map(args.filename)?;
 
-    println!("Part 1: {}", count_chains_containing_gold(&bags)?);
-    println!("Part 2: {}", sum_children(&bags, "shiny gold") - 1);
+    println!("Part 1: {}", bags.ancestors(&args.color).len());
+    println!("Part 2: {}", bags.weighted_descendants(&args.color)?);
 
     Ok(())
 }
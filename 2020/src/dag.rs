@@ -0,0 +1,281 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Debug, Display};
+
+use anyhow::{format_err, Result};
+use num_bigint::BigUint;
+
+use crate::dot::Dot;
+
+/// A directed graph with weighted edges that should contain no cycles. Every query that
+/// depends on the ordering fails if a cycle is found.
+#[derive(Clone, Debug)]
+pub struct Dag<N: Ord + Clone + Debug> {
+    edges: BTreeMap<N, BTreeMap<N, i64>>,
+    /// The same edges indexed by their destination.
+    reverse: BTreeMap<N, BTreeMap<N, i64>>,
+}
+
+impl<N: Ord + Clone + Debug> Default for Dag<N> {
+    fn default() -> Self {
+        Dag {
+            edges: BTreeMap::new(),
+            reverse: BTreeMap::new(),
+        }
+    }
+}
+
+impl<N: Ord + Clone + Debug> Dag<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_node(&mut self, node: N) {
+        self.reverse.entry(node.clone()).or_default();
+        self.edges.entry(node).or_default();
+    }
+
+    pub fn add_edge(&mut self, from: N, to: N) {
+        self.add_weighted_edge(from, to, 1);
+    }
+
+    pub fn add_weighted_edge(&mut self, from: N, to: N, weight: i64) {
+        self.add_node(from.clone());
+        self.add_node(to.clone());
+        self.reverse
+            .entry(to.clone())
+            .or_default()
+            .insert(from.clone(), weight);
+        self.edges.entry(from).or_default().insert(to, weight);
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = &N> {
+        self.edges.keys()
+    }
+
+    /// The direct successors of `node` and the weight of the edge to each.
+    pub fn successors(&self, node: &N) -> impl Iterator<Item = (&N, &i64)> {
+        self.edges
+            .get(node)
+            .into_iter()
+            .flat_map(|edges| edges.iter())
+    }
+
+    /// The nodes with an edge into `node` and the weight of each of those edges.
+    pub fn predecessors(&self, node: &N) -> impl Iterator<Item = (&N, &i64)> {
+        self.reverse
+            .get(node)
+            .into_iter()
+            .flat_map(|edges| edges.iter())
+    }
+
+    /// Every node with a path to `node`, not counting `node` itself.
+    pub fn ancestors(&self, node: &N) -> BTreeSet<N> {
+        let mut ancestors = BTreeSet::new();
+        let mut to_visit = vec![node];
+
+        while let Some(next) = to_visit.pop() {
+            for (predecessor, _) in self.predecessors(next) {
+                if ancestors.insert(predecessor.clone()) {
+                    to_visit.push(predecessor);
+                }
+            }
+        }
+
+        ancestors
+    }
+
+    /// For every node, the sum over its edges of weight × (1 + the total of the node the edge
+    /// leads to): how many things it holds if each edge means "holds `weight` of these".
+    pub fn descendant_totals(&self) -> Result<BTreeMap<N, i64>> {
+        let mut totals: BTreeMap<N, i64> = BTreeMap::new();
+
+        for node in self.topological_order()?.into_iter().rev() {
+            let total = self
+                .successors(&node)
+                .map(|(successor, weight)| weight * (1 + totals.get(successor).unwrap_or(&0)))
+                .sum();
+            totals.insert(node, total);
+        }
+
+        Ok(totals)
+    }
+
+    pub fn weighted_descendants(&self, node: &N) -> Result<i64> {
+        self.descendant_totals()?
+            .get(node)
+            .copied()
+            .ok_or_else(|| format_err!("{:?} is not in the graph", node))
+    }
+
+    /// Every node, each appearing before all of its successors.
+    pub fn topological_order(&self) -> Result<Vec<N>> {
+        let mut incoming: BTreeMap<&N, usize> = self.edges.keys().map(|node| (node, 0)).collect();
+        for edges in self.edges.values() {
+            for to in edges.keys() {
+                *incoming.entry(to).or_insert(0) += 1;
+            }
+        }
+
+        let mut ready: Vec<&N> = incoming
+            .iter()
+            .filter(|(_, count)| **count == 0)
+            .map(|(node, _)| *node)
+            .collect();
+        let mut order = Vec::new();
+
+        while let Some(node) = ready.pop() {
+            order.push(node.clone());
+
+            for (to, _) in self.successors(node) {
+                let count = incoming.entry(to).or_insert(0);
+                *count -= 1;
+                if *count == 0 {
+                    ready.push(to);
+                }
+            }
+        }
+
+        if order.len() < self.edges.len() {
+            let sorted: BTreeSet<&N> = order.iter().collect();
+            let stuck = self
+                .edges
+                .keys()
+                .find(|node| !sorted.contains(node))
+                .ok_or_else(|| format_err!("cycle detected"))?;
+
+            return Err(format_err!("cycle detected through {:?}", stuck));
+        }
+
+        Ok(order)
+    }
+
+    /// The number of distinct paths from `from` to `to`. Counts grow exponentially with the
+    /// number of branches, so they are not limited to a machine integer.
+    pub fn count_paths(&self, from: &N, to: &N) -> Result<BigUint> {
+        let mut counts: BTreeMap<&N, BigUint> = BTreeMap::new();
+
+        let order = self.topological_order()?;
+        for node in order.iter().rev() {
+            let count = if node == to {
+                BigUint::from(1u32)
+            } else {
+                self.successors(node)
+                    .filter_map(|(successor, _)| counts.get(successor))
+                    .sum()
+            };
+            counts.insert(node, count);
+        }
+
+        Ok(counts.remove(from).unwrap_or_default())
+    }
+
+    /// The smallest or largest total weight over paths from `from` to `to`, chosen by `better`.
+    fn best_path<F>(&self, from: &N, to: &N, better: F) -> Result<Option<i64>>
+    where
+        F: Fn(i64, i64) -> bool,
+    {
+        let mut distances: BTreeMap<N, i64> = BTreeMap::new();
+        distances.insert(from.clone(), 0);
+
+        for node in self.topological_order()? {
+            let distance = match distances.get(&node) {
+                Some(distance) => *distance,
+                None => continue,
+            };
+
+            for (successor, weight) in self.successors(&node) {
+                let candidate = distance + weight;
+                match distances.get(successor) {
+                    Some(existing) if !better(candidate, *existing) => {}
+                    _ => {
+                        distances.insert(successor.clone(), candidate);
+                    }
+                }
+            }
+        }
+
+        Ok(distances.get(to).copied())
+    }
+
+    /// The lowest total weight of any path from `from` to `to`, or None if there is no path.
+    pub fn shortest_path(&self, from: &N, to: &N) -> Result<Option<i64>> {
+        self.best_path(from, to, |candidate, existing| candidate < existing)
+    }
+
+    /// The highest total weight of any path from `from` to `to`, or None if there is no path.
+    pub fn longest_path(&self, from: &N, to: &N) -> Result<Option<i64>> {
+        self.best_path(from, to, |candidate, existing| candidate > existing)
+    }
+
+    /// Every node and edge as a Graphviz graph, labelling edges with their weights if
+    /// `show_weights` is set.
+    pub fn to_dot(&self, name: &str, show_weights: bool) -> Dot
+    where
+        N: Display,
+    {
+        let mut dot = Dot::new(name);
+        for node in self.nodes() {
+            dot.node(node);
+            for (successor, weight) in self.successors(node) {
+                let label = if show_weights {
+                    Some(weight.to_string())
+                } else {
+                    None
+                };
+                dot.edge(node, successor, label);
+            }
+        }
+
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paths() -> Result<()> {
+        let mut dag = Dag::new();
+        dag.add_weighted_edge('a', 'b', 2);
+        dag.add_weighted_edge('a', 'c', 1);
+        dag.add_weighted_edge('b', 'd', 1);
+        dag.add_weighted_edge('c', 'd', 5);
+        dag.add_weighted_edge('a', 'd', 4);
+        dag.add_node('e');
+
+        assert_eq!(dag.count_paths(&'a', &'d')?, BigUint::from(3u32));
+        assert_eq!(dag.count_paths(&'e', &'d')?, BigUint::from(0u32));
+        assert_eq!(dag.shortest_path(&'a', &'d')?, Some(3));
+        assert_eq!(dag.longest_path(&'a', &'d')?, Some(6));
+        assert_eq!(dag.shortest_path(&'d', &'a')?, None);
+
+        assert_eq!(
+            dag.ancestors(&'d'),
+            vec!['a', 'b', 'c'].into_iter().collect()
+        );
+        // a holds 2 b (each holding a d), 1 c (holding 5 d) and 4 d directly.
+        assert_eq!(dag.weighted_descendants(&'a')?, 2 * 2 + 6 + 4);
+
+        dag.add_edge('d', 'a');
+        assert!(dag.topological_order().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_count_overflows_i64() -> Result<()> {
+        // A chain of 70 diamonds has 2^70 paths from end to end.
+        let mut dag = Dag::new();
+        for i in 0..70 {
+            dag.add_edge(3 * i, 3 * i + 1);
+            dag.add_edge(3 * i, 3 * i + 2);
+            dag.add_edge(3 * i + 1, 3 * i + 3);
+            dag.add_edge(3 * i + 2, 3 * i + 3);
+        }
+
+        assert_eq!(dag.count_paths(&0, &210)?, BigUint::from(1u32) << 70);
+
+        Ok(())
+    }
+}
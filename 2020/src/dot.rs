@@ -0,0 +1,152 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// Quotes a Graphviz identifier, escaping anything that would end it early.
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// A directed graph written in Graphviz DOT format through its `Display` impl.
+#[derive(Clone, Debug, Default)]
+pub struct Dot {
+    name: String,
+    nodes: Vec<String>,
+    labels: BTreeMap<String, String>,
+    edges: Vec<(String, String, Option<String>)>,
+    highlighted_nodes: BTreeSet<String>,
+    highlighted_edges: BTreeSet<(String, String)>,
+}
+
+impl Dot {
+    pub fn new(name: &str) -> Self {
+        Dot {
+            name: name.to_string(),
+            ..Dot::default()
+        }
+    }
+
+    /// Adds a node, which is otherwise only drawn if an edge uses it.
+    pub fn node<S: ToString>(&mut self, node: S) {
+        let node = node.to_string();
+        if !self.nodes.contains(&node) {
+            self.nodes.push(node);
+        }
+    }
+
+    /// Shows `label` in place of the node's name.
+    pub fn label<S: ToString, L: ToString>(&mut self, node: S, label: L) {
+        let node = node.to_string();
+        self.node(node.clone());
+        self.labels.insert(node, label.to_string());
+    }
+
+    pub fn edge<S: ToString, T: ToString>(&mut self, from: S, to: T, label: Option<String>) {
+        self.edges.push((from.to_string(), to.to_string(), label));
+    }
+
+    pub fn highlight_node<S: ToString>(&mut self, node: S) {
+        self.highlighted_nodes.insert(node.to_string());
+    }
+
+    /// Highlights each node on `path` and the edges between consecutive nodes. The path may
+    /// follow an edge either way, e.g. up a tree and back down.
+    pub fn highlight_path<S: ToString>(&mut self, path: &[S]) {
+        let path: Vec<String> = path.iter().map(|node| node.to_string()).collect();
+
+        for node in path.iter() {
+            self.highlight_node(node);
+        }
+        for pair in path.windows(2) {
+            self.highlighted_edges
+                .insert((pair[0].clone(), pair[1].clone()));
+        }
+    }
+}
+
+impl fmt::Display for Dot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "digraph {} {{", quote(&self.name))?;
+
+        for node in self.nodes.iter() {
+            let mut attributes = Vec::new();
+            if let Some(label) = self.labels.get(node) {
+                attributes.push(format!("label={}", quote(label)));
+            }
+            if self.highlighted_nodes.contains(node) {
+                attributes.push("color=red".to_string());
+                attributes.push("style=bold".to_string());
+            }
+
+            if attributes.is_empty() {
+                writeln!(f, "    {};", quote(node))?;
+            } else {
+                writeln!(f, "    {} [{}];", quote(node), attributes.join(", "))?;
+            }
+        }
+
+        for (from, to, label) in self.edges.iter() {
+            let mut attributes = Vec::new();
+            if let Some(label) = label {
+                attributes.push(format!("label={}", quote(label)));
+            }
+            if self.highlighted_edges.contains(&(from.clone(), to.clone()))
+                || self.highlighted_edges.contains(&(to.clone(), from.clone()))
+            {
+                attributes.push("color=red".to_string());
+                attributes.push("penwidth=2".to_string());
+            }
+
+            if attributes.is_empty() {
+                writeln!(f, "    {} -> {};", quote(from), quote(to))?;
+            } else {
+                writeln!(
+                    f,
+                    "    {} -> {} [{}];",
+                    quote(from),
+                    quote(to),
+                    attributes.join(", ")
+                )?;
+            }
+        }
+
+        writeln!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let mut dot = Dot::new("bags");
+        dot.node("shiny gold");
+        dot.edge("shiny gold", "dark \"red\"", Some("2".to_string()));
+        dot.edge("dark \"red\"", "faded blue", None);
+        dot.highlight_path(&["shiny gold", "dark \"red\""]);
+
+        assert_eq!(
+            dot.to_string(),
+            "digraph \"bags\" {\n\
+             \x20   \"shiny gold\" [color=red, style=bold];\n\
+             \x20   \"shiny gold\" -> \"dark \\\"red\\\"\" [label=\"2\", color=red, penwidth=2];\n\
+             \x20   \"dark \\\"red\\\"\" -> \"faded blue\";\n\
+             }\n"
+        );
+    }
+
+    #[test]
+    fn test_highlight_path_up_and_down() {
+        // A tree drawn parent -> child, with a path climbing from one leaf to the other.
+        let mut dot = Dot::new("tree");
+        dot.edge("root", "left", None);
+        dot.edge("root", "right", None);
+        dot.edge("right", "other", None);
+        dot.highlight_path(&["left", "root", "right"]);
+
+        let output = dot.to_string();
+        assert!(output.contains("\"root\" -> \"left\" [color=red, penwidth=2];"));
+        assert!(output.contains("\"root\" -> \"right\" [color=red, penwidth=2];"));
+        assert!(output.contains("    \"right\" -> \"other\";"));
+    }
+}
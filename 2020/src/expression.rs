@@ -0,0 +1,370 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use anyhow::{format_err, Result};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Associativity {
+    Left,
+    Right,
+}
+
+/// A binary operator. Higher precedence binds more tightly.
+#[derive(Clone, Debug)]
+pub struct Operator {
+    pub symbol: String,
+    pub precedence: u32,
+    pub associativity: Associativity,
+    apply: fn(i64, i64) -> i64,
+}
+
+/// The binary operators an expression may use, decided at runtime.
+#[derive(Clone, Debug, Default)]
+pub struct OperatorTable {
+    operators: Vec<Operator>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expression {
+    Number(i64),
+    Variable(String),
+    Binary {
+        operator: String,
+        left: Box<Expression>,
+        right: Box<Expression>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Number(i64),
+    Name(String),
+    Operator(String),
+    Open,
+    Close,
+}
+
+impl OperatorTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an operator, replacing any existing one with the same symbol. Fails for symbols the
+    /// tokenizer could never match: empty ones, ones containing whitespace or parentheses, and
+    /// ones starting with a digit.
+    pub fn with(
+        mut self,
+        symbol: &str,
+        precedence: u32,
+        associativity: Associativity,
+        apply: fn(i64, i64) -> i64,
+    ) -> Result<Self> {
+        if symbol.is_empty()
+            || symbol.starts_with(|c: char| c.is_ascii_digit())
+            || symbol.contains(|c: char| c.is_whitespace() || c == '(' || c == ')')
+        {
+            return Err(format_err!("\"{}\" can't be an operator", symbol));
+        }
+
+        self.operators.retain(|operator| operator.symbol != symbol);
+        self.operators.push(Operator {
+            symbol: symbol.to_string(),
+            precedence,
+            associativity,
+            apply,
+        });
+        Ok(self)
+    }
+
+    pub fn get(&self, symbol: &str) -> Option<&Operator> {
+        self.operators
+            .iter()
+            .find(|operator| operator.symbol == symbol)
+    }
+
+    /// Splits `input` into tokens along with the column each starts at. Operator symbols are
+    /// matched longest first, so "**" wins over "*" when both are in the table, and before
+    /// names, so alphabetic operators such as "mod" work. An alphabetic symbol only matches a
+    /// whole word, so "model" is still a name.
+    fn tokenize(&self, input: &str) -> Result<Vec<(usize, Token)>> {
+        let is_word = |c: char| c.is_alphanumeric() || c == '_';
+
+        let mut tokens = Vec::new();
+        let mut position = 0;
+
+        while position < input.len() {
+            let rest = &input[position..];
+            let c = rest.chars().next().unwrap();
+
+            if c.is_whitespace() {
+                position += c.len_utf8();
+                continue;
+            }
+
+            let operator = self
+                .operators
+                .iter()
+                .filter(|operator| {
+                    let symbol = operator.symbol.as_str();
+                    rest.starts_with(symbol)
+                        && !(symbol.ends_with(is_word) && rest[symbol.len()..].starts_with(is_word))
+                })
+                .max_by_key(|operator| operator.symbol.len());
+
+            let (length, token) = if c == '(' {
+                (1, Token::Open)
+            } else if c == ')' {
+                (1, Token::Close)
+            } else if c.is_ascii_digit() {
+                let digits = rest
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(rest.len());
+                (digits, Token::Number(rest[..digits].parse()?))
+            } else if let Some(operator) = operator {
+                (
+                    operator.symbol.len(),
+                    Token::Operator(operator.symbol.clone()),
+                )
+            } else if c.is_alphabetic() || c == '_' {
+                let name = rest.find(|c: char| !is_word(c)).unwrap_or(rest.len());
+                (name, Token::Name(rest[..name].to_string()))
+            } else {
+                return Err(format_err!(
+                    "Unknown operator at column {} of \"{}\"",
+                    position + 1,
+                    input
+                ));
+            };
+
+            tokens.push((position, token));
+            position += length;
+        }
+
+        Ok(tokens)
+    }
+
+    pub fn parse(&self, input: &str) -> Result<Expression> {
+        let tokens = self.tokenize(input)?;
+        let mut parser = Parser {
+            table: self,
+            input,
+            tokens: &tokens,
+            position: 0,
+        };
+
+        let expression = parser.expression(0)?;
+        if parser.position < tokens.len() {
+            return Err(parser.unexpected());
+        }
+
+        Ok(expression)
+    }
+}
+
+/// Precedence climbing over a token list.
+struct Parser<'a> {
+    table: &'a OperatorTable,
+    input: &'a str,
+    tokens: &'a [(usize, Token)],
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn unexpected(&self) -> anyhow::Error {
+        match self.tokens.get(self.position) {
+            Some((column, _)) => format_err!(
+                "Unexpected token at column {} of \"{}\"",
+                column + 1,
+                self.input
+            ),
+            None => format_err!("Unexpected end of \"{}\"", self.input),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expression> {
+        let token = self.tokens.get(self.position).map(|(_, token)| token);
+        self.position += 1;
+
+        match token {
+            Some(Token::Number(value)) => Ok(Expression::Number(*value)),
+            Some(Token::Name(name)) => Ok(Expression::Variable(name.clone())),
+            Some(Token::Open) => {
+                let inner = self.expression(0)?;
+                match self.tokens.get(self.position) {
+                    Some((_, Token::Close)) => {
+                        self.position += 1;
+                        Ok(inner)
+                    }
+                    _ => Err(self.unexpected()),
+                }
+            }
+            _ => {
+                self.position -= 1;
+                Err(self.unexpected())
+            }
+        }
+    }
+
+    /// Parses operators binding at least as tightly as `min_precedence`, which is wider than
+    /// an operator's precedence so that one more than `u32::MAX` still fits.
+    fn expression(&mut self, min_precedence: u64) -> Result<Expression> {
+        let mut left = self.primary()?;
+
+        while let Some((_, Token::Operator(symbol))) = self.tokens.get(self.position) {
+            let operator = match self.table.get(symbol) {
+                Some(operator) if u64::from(operator.precedence) >= min_precedence => operator,
+                _ => break,
+            };
+            self.position += 1;
+
+            let next_precedence = match operator.associativity {
+                Associativity::Left => u64::from(operator.precedence) + 1,
+                Associativity::Right => u64::from(operator.precedence),
+            };
+            let right = self.expression(next_precedence)?;
+
+            left = Expression::Binary {
+                operator: symbol.clone(),
+                left: Box::new(left),
+                right: Box::new(right),
+            };
+        }
+
+        Ok(left)
+    }
+}
+
+impl Expression {
+    /// Evaluates the expression with `table`'s operators, looking names up in `variables`.
+    pub fn evaluate(
+        &self,
+        table: &OperatorTable,
+        variables: &BTreeMap<String, i64>,
+    ) -> Result<i64> {
+        match self {
+            Expression::Number(value) => Ok(*value),
+            Expression::Variable(name) => variables
+                .get(name)
+                .copied()
+                .ok_or_else(|| format_err!("{} has no value", name)),
+            Expression::Binary {
+                operator,
+                left,
+                right,
+            } => {
+                let apply = table
+                    .get(operator)
+                    .ok_or_else(|| format_err!("Unknown operator {}", operator))?
+                    .apply;
+
+                Ok(apply(
+                    left.evaluate(table, variables)?,
+                    right.evaluate(table, variables)?,
+                ))
+            }
+        }
+    }
+}
+
+/// Writes every binary operation in parentheses, so the grouping the parser chose is explicit.
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expression::Number(value) => write!(f, "{}", value),
+            Expression::Variable(name) => write!(f, "{}", name),
+            Expression::Binary {
+                operator,
+                left,
+                right,
+            } => write!(f, "({} {} {})", left, operator, right),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evaluate(table: &OperatorTable, input: &str) -> Result<i64> {
+        table.parse(input)?.evaluate(table, &BTreeMap::new())
+    }
+
+    #[test]
+    fn test_precedence() -> Result<()> {
+        let equal = OperatorTable::new()
+            .with("+", 1, Associativity::Left, |a, b| a + b)?
+            .with("*", 1, Associativity::Left, |a, b| a * b)?;
+        let plus_first = equal
+            .clone()
+            .with("+", 2, Associativity::Left, |a, b| a + b)?;
+
+        let input = "((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2";
+        assert_eq!(evaluate(&equal, input)?, 13632);
+        assert_eq!(evaluate(&plus_first, input)?, 23340);
+        assert_eq!(evaluate(&plus_first, "2 * 3 + (4 * 5)")?, 46);
+
+        assert_eq!(plus_first.parse("1 * 2 + 3")?.to_string(), "(1 * (2 + 3))");
+
+        let power = OperatorTable::new()
+            .with("-", 1, Associativity::Left, |a, b| a - b)?
+            .with("**", 2, Associativity::Right, |a, b| a.pow(b as u32))?;
+        assert_eq!(power.parse("2 ** 3 ** 2")?.to_string(), "(2 ** (3 ** 2))");
+        assert_eq!(evaluate(&power, "10 - 4 - 3")?, 3);
+
+        assert!(equal.parse("(1 + 2").is_err());
+        assert!(equal.parse("1 + + 2").is_err());
+        assert!(equal.parse("1 - 2").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_word_operators() -> Result<()> {
+        let table = OperatorTable::new()
+            .with("+", 1, Associativity::Left, |a, b| a + b)?
+            .with("mod", 2, Associativity::Left, |a, b| a % b)?;
+
+        assert_eq!(evaluate(&table, "1 + 17 mod 5")?, 3);
+        assert_eq!(table.parse("(7)mod(4)")?.to_string(), "(7 mod 4)");
+        assert_eq!(table.parse("model + mod_2")?.to_string(), "(model + mod_2)");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_extreme_precedence() -> Result<()> {
+        let table = OperatorTable::new()
+            .with("-", u32::MAX, Associativity::Left, |a, b| a - b)?
+            .with("^", u32::MAX, Associativity::Right, |a, b| a.pow(b as u32))?;
+
+        assert_eq!(evaluate(&table, "10 - 4 - 3")?, 3);
+        assert_eq!(table.parse("2 ^ 3 ^ 2")?.to_string(), "(2 ^ (3 ^ 2))");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_bad_symbols() {
+        let add = |a, b| a + b;
+        for symbol in ["", "a b", "(", "+)", "2x"].iter() {
+            assert!(OperatorTable::new()
+                .with(symbol, 1, Associativity::Left, add)
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn test_variables() -> Result<()> {
+        let table = OperatorTable::new().with("+", 1, Associativity::Left, |a, b| a + b)?;
+        let expression = table.parse("x + (y + 1)")?;
+
+        let mut variables = BTreeMap::new();
+        variables.insert("x".to_string(), 2);
+        assert!(expression.evaluate(&table, &variables).is_err());
+
+        variables.insert("y".to_string(), 3);
+        assert_eq!(expression.evaluate(&table, &variables)?, 6);
+
+        Ok(())
+    }
+}
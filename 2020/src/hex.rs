@@ -0,0 +1,278 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{format_err, Error, Result};
+
+use crate::automaton::{self, Cell, Rule};
+
+/// A direction on a pointy-topped hex grid.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum HexDirection {
+    East,
+    NorthEast,
+    NorthWest,
+    West,
+    SouthWest,
+    SouthEast,
+}
+
+impl HexDirection {
+    pub fn all() -> [HexDirection; 6] {
+        [
+            HexDirection::East,
+            HexDirection::NorthEast,
+            HexDirection::NorthWest,
+            HexDirection::West,
+            HexDirection::SouthWest,
+            HexDirection::SouthEast,
+        ]
+    }
+
+    fn offset(self) -> (i64, i64) {
+        match self {
+            HexDirection::East => (1, 0),
+            HexDirection::NorthEast => (1, -1),
+            HexDirection::NorthWest => (0, -1),
+            HexDirection::West => (-1, 0),
+            HexDirection::SouthWest => (-1, 1),
+            HexDirection::SouthEast => (0, 1),
+        }
+    }
+}
+
+impl FromStr for HexDirection {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "e" => Ok(HexDirection::East),
+            "ne" => Ok(HexDirection::NorthEast),
+            "nw" => Ok(HexDirection::NorthWest),
+            "w" => Ok(HexDirection::West),
+            "sw" => Ok(HexDirection::SouthWest),
+            "se" => Ok(HexDirection::SouthEast),
+            _ => Err(format_err!("unknown hex direction \"{}\"", value)),
+        }
+    }
+}
+
+impl fmt::Display for HexDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                HexDirection::East => "e",
+                HexDirection::NorthEast => "ne",
+                HexDirection::NorthWest => "nw",
+                HexDirection::West => "w",
+                HexDirection::SouthWest => "sw",
+                HexDirection::SouthEast => "se",
+            }
+        )
+    }
+}
+
+/// Parses an undelimited run of directions such as "esenee".
+pub fn parse_path(value: &str) -> Result<Vec<HexDirection>> {
+    let mut directions = Vec::new();
+
+    let mut start = 0;
+    for (i, c) in value.char_indices() {
+        if c == 'n' || c == 's' {
+            continue;
+        }
+
+        let end = i + c.len_utf8();
+        directions.push(value[start..end].parse()?);
+        start = end;
+    }
+
+    if start != value.len() {
+        return Err(format_err!("dangling direction \"{}\"", &value[start..]));
+    }
+
+    Ok(directions)
+}
+
+/// A hex in axial coordinates. The implied cube coordinate is `-q - r`.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Hex {
+    pub q: i64,
+    pub r: i64,
+}
+
+impl Hex {
+    pub fn new(q: i64, r: i64) -> Self {
+        Self { q, r }
+    }
+
+    pub fn s(&self) -> i64 {
+        -self.q - self.r
+    }
+
+    pub fn cube(&self) -> (i64, i64, i64) {
+        (self.q, self.r, self.s())
+    }
+
+    pub fn neighbor(&self, direction: HexDirection) -> Hex {
+        self.offset(direction, 1)
+    }
+
+    pub fn offset(&self, direction: HexDirection, distance: i64) -> Hex {
+        let (q, r) = direction.offset();
+        Hex::new(self.q + q * distance, self.r + r * distance)
+    }
+
+    pub fn follow(&self, path: &[HexDirection]) -> Hex {
+        path.iter()
+            .fold(*self, |hex, direction| hex.neighbor(*direction))
+    }
+
+    pub fn distance(&self, other: &Hex) -> i64 {
+        let q = (self.q - other.q).abs();
+        let r = (self.r - other.r).abs();
+        let s = (self.s() - other.s()).abs();
+
+        (q + r + s) / 2
+    }
+
+    /// Every hex exactly `radius` steps away, walking around the ring from its western corner.
+    pub fn ring(&self, radius: i64) -> Vec<Hex> {
+        if radius == 0 {
+            return vec![*self];
+        }
+
+        let mut hexes = Vec::new();
+        let mut hex = self.offset(HexDirection::West, radius);
+        for direction in [
+            HexDirection::NorthEast,
+            HexDirection::East,
+            HexDirection::SouthEast,
+            HexDirection::SouthWest,
+            HexDirection::West,
+            HexDirection::NorthWest,
+        ]
+        .iter()
+        {
+            for _ in 0..radius {
+                hexes.push(hex);
+                hex = hex.neighbor(*direction);
+            }
+        }
+
+        hexes
+    }
+}
+
+impl Cell for Hex {
+    fn neighbors(&self) -> Vec<Hex> {
+        HexDirection::all()
+            .iter()
+            .map(|direction| self.neighbor(*direction))
+            .collect()
+    }
+}
+
+impl fmt::Display for Hex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.q, self.r)
+    }
+}
+
+/// An unbounded hex grid where every tile is either active or inactive.
+#[derive(Clone, Debug, Default)]
+pub struct HexMap {
+    active: HashSet<Hex>,
+}
+
+impl HexMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_active(&self, hex: &Hex) -> bool {
+        self.active.contains(hex)
+    }
+
+    pub fn set_active(&mut self, hex: Hex, active: bool) {
+        if active {
+            self.active.insert(hex);
+        } else {
+            self.active.remove(&hex);
+        }
+    }
+
+    /// Flips a tile, returning its new state.
+    pub fn toggle(&mut self, hex: Hex) -> bool {
+        let active = !self.is_active(&hex);
+        self.set_active(hex, active);
+        active
+    }
+
+    pub fn count_active(&self) -> usize {
+        self.active.len()
+    }
+
+    pub fn active(&self) -> impl Iterator<Item = &Hex> {
+        self.active.iter()
+    }
+
+    pub fn step(&self, rule: &Rule) -> HexMap {
+        HexMap {
+            active: automaton::step(&self.active, rule),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_path() -> Result<()> {
+        let path = parse_path("nwwswee")?;
+        assert_eq!(Hex::default().follow(&path), Hex::default());
+
+        assert_eq!(Hex::default().follow(&parse_path("esew")?), Hex::new(0, 1));
+        assert!(parse_path("en").is_err());
+        assert!(parse_path("eé").is_err());
+        assert!(parse_path("nö").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ring_and_distance() {
+        let center = Hex::new(2, -1);
+
+        for radius in 0..4 {
+            let ring = center.ring(radius);
+            assert_eq!(ring.len(), std::cmp::max(1, 6 * radius) as usize);
+            assert!(ring.iter().all(|hex| hex.distance(&center) == radius));
+        }
+    }
+
+    #[test]
+    fn test_step() {
+        // Two adjacent hexes share two neighbors, which are both born under B2/S12.
+        let mut map = HexMap::new();
+        map.set_active(Hex::new(0, 0), true);
+        map.set_active(Hex::new(1, 0), true);
+
+        let map = map.step(&Rule::new(&[2], &[1, 2]));
+
+        let mut active: Vec<Hex> = map.active().copied().collect();
+        active.sort();
+        assert_eq!(
+            active,
+            vec![
+                Hex::new(0, 0),
+                Hex::new(0, 1),
+                Hex::new(1, -1),
+                Hex::new(1, 0)
+            ]
+        );
+    }
+}
@@ -1,42 +1,8 @@
-use std::collections::{HashMap, VecDeque};
-
 use anyhow::{anyhow, Result};
-use aoc_2021::{read_matrix, Args, Matrix, Point};
+use aoc_2021::components::connected_components;
+use aoc_2021::{read_matrix, Args, Matrix};
 use structopt::StructOpt;
 
-fn flood_fill(
-    matrix: &Matrix<usize>,
-    basin_id: usize,
-    point: Point,
-    basin_ids: &mut HashMap<Point, usize>,
-) {
-    let mut to_visit = VecDeque::new();
-    to_visit.push_back(point);
-
-    while let Some(next_point) = to_visit.pop_front() {
-        if basin_ids.contains_key(&next_point) {
-            continue;
-        }
-
-        let value = match matrix.get(next_point.x, next_point.y) {
-            Some(value) => value,
-            None => {
-                continue;
-            }
-        };
-
-        if value == 9 {
-            continue;
-        }
-
-        for neighbor in matrix.cardinal_neighbor_coordinates(next_point.x, next_point.y) {
-            to_visit.push_back(neighbor);
-        }
-
-        basin_ids.insert(next_point, basin_id);
-    }
-}
-
 fn main() -> Result<()> {
     let args = Args::from_args();
 
@@ -64,42 +30,9 @@ fn main() -> Result<()> {
 
     println!("{}", risk_level);
 
-    let mut basin_ids = HashMap::new();
-    let mut current_basin_id = 0;
-
-    for i in 0..matrix.height {
-        for j in 0..matrix.width {
-            let point = Point::new(j, i);
-            if let Some(9) = matrix.get(j, i) {
-                continue;
-            }
-
-            if !basin_ids.contains_key(&point) {
-                flood_fill(&matrix, current_basin_id, point, &mut basin_ids);
-                current_basin_id += 1;
-            }
-        }
-    }
-
-    /*
-    for i in 0..matrix.height {
-        for j in 0..matrix.width {
-            let id = basin_ids
-                .get(&Point::new(j, i))
-                .map(|i| i + 65)
-                .unwrap_or(64) as u32;
-            print!("{}", char::from_u32(id).unwrap());
-        }
-        println!("");
-    }
-    */
-
-    let mut basin_sizes = HashMap::new();
-    for id in basin_ids.into_values() {
-        *basin_sizes.entry(id).or_insert(0) += 1;
-    }
+    let basins = connected_components(&matrix, |value| *value != 9);
 
-    let mut sorted_sizes: Vec<usize> = basin_sizes.into_values().collect();
+    let mut sorted_sizes = basins.sizes;
     sorted_sizes.sort_by(|a, b| b.cmp(a));
 
     println!("{}", sorted_sizes[0] * sorted_sizes[1] * sorted_sizes[2]);
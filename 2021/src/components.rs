@@ -0,0 +1,215 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+use crate::{Matrix, Point};
+
+/// A finite grid whose cells can be flood filled through their cardinal neighbours.
+pub trait Grid {
+    type Position: Clone + Eq + Hash;
+    type Tile;
+
+    fn positions(&self) -> Vec<Self::Position>;
+
+    fn tile(&self, position: &Self::Position) -> Option<Self::Tile>;
+
+    fn adjacent(&self, position: &Self::Position) -> Vec<Self::Position>;
+}
+
+impl<T: Copy> Grid for Matrix<T> {
+    type Position = Point;
+    type Tile = T;
+
+    fn positions(&self) -> Vec<Point> {
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| Point::new(x, y)))
+            .collect()
+    }
+
+    fn tile(&self, position: &Point) -> Option<T> {
+        self.get(position.x, position.y)
+    }
+
+    fn adjacent(&self, position: &Point) -> Vec<Point> {
+        self.cardinal_neighbor_coordinates(position.x, position.y)
+    }
+}
+
+/// Passable cells grouped into cardinally connected components.
+#[derive(Debug)]
+pub struct Components<P> {
+    /// The component id of every passable cell.
+    pub ids: HashMap<P, usize>,
+    /// The number of cells in each component, indexed by id.
+    pub sizes: Vec<usize>,
+    /// The impassable cells adjacent to each component, indexed by id.
+    pub boundaries: Vec<HashSet<P>>,
+}
+
+impl<P: Eq + Hash> Components<P> {
+    pub fn len(&self) -> usize {
+        self.sizes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sizes.is_empty()
+    }
+
+    pub fn component_of(&self, position: &P) -> Option<usize> {
+        self.ids.get(position).copied()
+    }
+}
+
+fn is_passable<G, F>(grid: &G, position: &G::Position, passable: &F) -> bool
+where
+    G: Grid,
+    F: Fn(&G::Tile) -> bool,
+{
+    grid.tile(position)
+        .map(|tile| passable(&tile))
+        .unwrap_or(false)
+}
+
+/// Returns every passable cell reachable from `start`, including `start` itself if passable.
+pub fn flood_fill<G, F>(grid: &G, start: G::Position, passable: F) -> HashSet<G::Position>
+where
+    G: Grid,
+    F: Fn(&G::Tile) -> bool,
+{
+    distance_field(grid, vec![start], passable)
+        .into_keys()
+        .collect()
+}
+
+pub fn connected_components<G, F>(grid: &G, passable: F) -> Components<G::Position>
+where
+    G: Grid,
+    F: Fn(&G::Tile) -> bool,
+{
+    let mut components = Components {
+        ids: HashMap::new(),
+        sizes: Vec::new(),
+        boundaries: Vec::new(),
+    };
+
+    for start in grid.positions() {
+        if components.ids.contains_key(&start) || !is_passable(grid, &start, &passable) {
+            continue;
+        }
+
+        let id = components.sizes.len();
+        let mut size = 0;
+        let mut boundary = HashSet::new();
+
+        let mut to_visit = VecDeque::new();
+        components.ids.insert(start.clone(), id);
+        to_visit.push_back(start);
+
+        while let Some(position) = to_visit.pop_front() {
+            size += 1;
+
+            for neighbor in grid.adjacent(&position) {
+                if !is_passable(grid, &neighbor, &passable) {
+                    boundary.insert(neighbor);
+                    continue;
+                }
+
+                if components.ids.contains_key(&neighbor) {
+                    continue;
+                }
+
+                components.ids.insert(neighbor.clone(), id);
+                to_visit.push_back(neighbor);
+            }
+        }
+
+        components.sizes.push(size);
+        components.boundaries.push(boundary);
+    }
+
+    components
+}
+
+/// Breadth-first distance from the nearest of `sources` to every reachable passable cell.
+pub fn distance_field<G, F, I>(grid: &G, sources: I, passable: F) -> HashMap<G::Position, usize>
+where
+    G: Grid,
+    F: Fn(&G::Tile) -> bool,
+    I: IntoIterator<Item = G::Position>,
+{
+    let mut distances = HashMap::new();
+    let mut to_visit = VecDeque::new();
+
+    for source in sources {
+        if is_passable(grid, &source, &passable) && !distances.contains_key(&source) {
+            distances.insert(source.clone(), 0);
+            to_visit.push_back(source);
+        }
+    }
+
+    while let Some(position) = to_visit.pop_front() {
+        let distance = distances[&position];
+
+        for neighbor in grid.adjacent(&position) {
+            if distances.contains_key(&neighbor) || !is_passable(grid, &neighbor, &passable) {
+                continue;
+            }
+
+            distances.insert(neighbor.clone(), distance + 1);
+            to_visit.push_back(neighbor);
+        }
+    }
+
+    distances
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix() -> Matrix<char> {
+        let data: Vec<Vec<char>> = ["..#..", "..#..", "###.#", "....."]
+            .iter()
+            .map(|row| row.chars().collect())
+            .collect();
+
+        Matrix {
+            data,
+            width: 5,
+            height: 4,
+        }
+    }
+
+    #[test]
+    fn test_connected_components() {
+        let components = connected_components(&matrix(), |c| *c == '.');
+
+        assert_eq!(components.sizes, vec![4, 10]);
+        assert_eq!(
+            components.component_of(&Point::new(4, 3)),
+            components.component_of(&Point::new(3, 0))
+        );
+        assert_eq!(components.component_of(&Point::new(2, 0)), None);
+        assert_eq!(
+            components.boundaries[0],
+            vec![
+                Point::new(2, 0),
+                Point::new(2, 1),
+                Point::new(0, 2),
+                Point::new(1, 2)
+            ]
+            .into_iter()
+            .collect()
+        );
+    }
+
+    #[test]
+    fn test_distance_field() {
+        let distances = distance_field(&matrix(), vec![Point::new(0, 3), Point::new(4, 0)], |c| {
+            *c == '.'
+        });
+
+        assert_eq!(distances[&Point::new(3, 3)], 3);
+        assert_eq!(distances[&Point::new(3, 2)], 3);
+        assert!(!distances.contains_key(&Point::new(0, 0)));
+    }
+}
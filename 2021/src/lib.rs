@@ -1,35 +1,163 @@
+pub mod components;
+pub mod neighbors;
+pub mod parse_error;
+pub mod range_set;
+pub mod segment;
+pub mod template;
+
 use std::collections::HashSet;
 use std::error::Error;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::marker::PhantomData;
 use std::str::FromStr;
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
 use structopt::StructOpt;
 
+use crate::neighbors::{Stencil, Topology};
+use crate::parse_error::ParseError;
+
 #[derive(StructOpt)]
 pub struct Args {
     pub filename: String,
 }
 
+/// Opens `filename` for buffered reading, treating "-" as stdin.
+pub fn open_input(filename: &str) -> Result<Box<dyn BufRead>> {
+    if filename == "-" {
+        return Ok(Box::new(BufReader::new(std::io::stdin())));
+    }
+
+    let file = File::open(filename).with_context(|| format!("Can't open {}", filename))?;
+    Ok(Box::new(BufReader::new(file)))
+}
+
+/// Parses the non-empty lines of a reader one at a time, so the input is never held in
+/// memory all at once.
+pub struct ParsedLines<R, T> {
+    reader: R,
+    name: String,
+    line_number: usize,
+    buffer: String,
+    done: bool,
+    marker: PhantomData<T>,
+}
+
+impl<R, T> Iterator for ParsedLines<R, T>
+where
+    R: BufRead,
+    T: FromStr,
+    <T as FromStr>::Err: 'static + Error + Send + Sync,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Result<T>> {
+        while !self.done {
+            self.buffer.clear();
+            match self.reader.read_line(&mut self.buffer) {
+                Ok(0) => self.done = true,
+                Ok(_) => {
+                    self.line_number += 1;
+                    let line = self.buffer.strip_suffix('\n').unwrap_or(&self.buffer);
+                    let line = line.strip_suffix('\r').unwrap_or(line);
+                    if line.is_empty() {
+                        continue;
+                    }
+
+                    return Some(line.parse().map_err(|err| {
+                        ParseError::new(&self.name, self.line_number, line, err).into()
+                    }));
+                }
+                Err(err) => {
+                    // A failed read can't be resumed.
+                    self.done = true;
+                    return Some(Err(err.into()));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Lazily parses each non-empty line of `reader` as a `T`. `name` is used in errors.
+pub fn parse_lines<R: BufRead, T>(reader: R, name: &str) -> ParsedLines<R, T> {
+    ParsedLines {
+        reader,
+        name: name.to_string(),
+        line_number: 0,
+        buffer: String::new(),
+        done: false,
+        marker: PhantomData,
+    }
+}
+
+/// Lazily parses each non-empty line of `filename`, or of stdin if it is "-".
+pub fn lines_iter<T>(filename: &str) -> Result<ParsedLines<Box<dyn BufRead>, T>>
+where
+    T: FromStr,
+    <T as FromStr>::Err: 'static + Error + Send + Sync,
+{
+    Ok(parse_lines(open_input(filename)?, filename))
+}
+
+/// Parses each non-empty line as a `T`. A line that fails becomes a `ParseError` naming it.
 pub fn read_lines<T>(filename: &str) -> Result<Vec<T>>
 where
     T: FromStr,
     <T as FromStr>::Err: 'static + Error + Send + Sync,
 {
-    let contents = std::fs::read_to_string(filename)?;
+    lines_iter(filename)?.collect()
+}
+
+/// Parses blocks of lines separated by blank lines, each block as one `T`. A block that fails
+/// becomes a `ParseError` naming the line it starts on. `name` is used in errors.
+pub fn parse_records<R: BufRead, T>(reader: R, name: &str) -> Result<Vec<T>>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Into<anyhow::Error>,
+{
+    let lines = reader.lines();
 
     let mut values = Vec::new();
-    for line in contents.split("\n") {
-        if line.is_empty() {
+    let mut block: Vec<String> = Vec::new();
+    let mut start = 0;
+    for (index, line) in lines.chain(std::iter::once(Ok(String::new()))).enumerate() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            if block.is_empty() {
+                start = index + 1;
+            }
+            block.push(line);
             continue;
         }
 
-        values.push(line.parse()?);
+        if block.is_empty() {
+            continue;
+        }
+
+        let text = block.join("\n");
+        let value = text.parse().map_err(|err: <T as FromStr>::Err| {
+            ParseError::from_anyhow(name, start, &text, err.into())
+        })?;
+        values.push(value);
+        block.clear();
     }
 
     Ok(values)
 }
 
-#[derive(Hash, Eq, PartialEq, Debug)]
+/// Reads blocks of lines separated by blank lines from `filename`, parsing each as one `T`.
+pub fn read_records<T>(filename: &str) -> Result<Vec<T>>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Into<anyhow::Error>,
+{
+    parse_records(open_input(filename)?, filename)
+}
+
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
 pub struct Point {
     pub x: usize,
     pub y: usize,
@@ -41,6 +169,11 @@ impl Point {
     }
 }
 
+pub struct CoordinateValue<T> {
+    pub coordinates: Point,
+    pub value: T,
+}
+
 #[derive(Debug)]
 pub struct Matrix<T: Copy> {
     data: Vec<Vec<T>>,
@@ -54,65 +187,27 @@ impl<T: Copy> Matrix<T> {
     }
 
     pub fn cardinal_neighbor_coordinates(&self, x: usize, y: usize) -> Vec<Point> {
-        let mut coordinates = Vec::new();
-
-        if x > 0 {
-            coordinates.push(Point::new(x - 1, y));
-        }
-
-        if x < self.width - 1 {
-            coordinates.push(Point::new(x + 1, y));
-        }
-
-        if y > 0 {
-            coordinates.push(Point::new(x, y - 1));
-        }
-
-        if y < self.height - 1 {
-            coordinates.push(Point::new(x, y + 1));
-        }
-
-        coordinates
+        self.neighbors(x, y, Topology::Bounded, Stencil::Cardinal)
+            .map(|neighbor| neighbor.coordinates)
+            .collect()
     }
 
     pub fn cardinal_neighbors(&self, x: usize, y: usize) -> Vec<T> {
-        let mut neighbors = Vec::new();
-
-        for coordinates in self.cardinal_neighbor_coordinates(x, y) {
-            if let Some(value) = self.get(coordinates.x, coordinates.y) {
-                neighbors.push(value);
-            }
-        }
-
-        neighbors
+        self.neighbors(x, y, Topology::Bounded, Stencil::Cardinal)
+            .map(|neighbor| neighbor.value)
+            .collect()
     }
 
     pub fn all_neighbor_coordinates(&self, x: usize, y: usize) -> Vec<Point> {
-        let mut coordinates = Vec::new();
-
-        for i in y - 1..=y + 1 {
-            for j in x - 1..=x + 1 {
-                if i == j {
-                    continue;
-                }
-
-                coordinates.push(Point::new(j, i));
-            }
-        }
-
-        coordinates
+        self.neighbors(x, y, Topology::Bounded, Stencil::All)
+            .map(|neighbor| neighbor.coordinates)
+            .collect()
     }
 
     pub fn all_neighbors(&self, x: usize, y: usize) -> Vec<T> {
-        let mut neighbors = Vec::new();
-
-        for coordinates in self.all_neighbor_coordinates(x, y) {
-            if let Some(value) = self.get(coordinates.x, coordinates.y) {
-                neighbors.push(value);
-            }
-        }
-
-        neighbors
+        self.neighbors(x, y, Topology::Bounded, Stencil::All)
+            .map(|neighbor| neighbor.value)
+            .collect()
     }
 }
 
@@ -121,13 +216,12 @@ where
     T: TryFrom<u32> + Copy,
     <T as TryFrom<u32>>::Error: 'static + Send + Sync + Error,
 {
-    let contents = std::fs::read_to_string(filename)?;
-
     let mut data: Vec<Vec<T>> = Vec::new();
     let mut widths = HashSet::new();
     let mut height = 0;
 
-    for line in contents.lines() {
+    for line in open_input(filename)?.lines() {
+        let line = line?;
         height += 1;
 
         let mut row: Vec<T> = Vec::new();
@@ -153,3 +247,45 @@ where
         height,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lines() {
+        let input = std::io::Cursor::new("1\n\n2\r\nx\n4");
+        let values: Vec<Result<u32>> = parse_lines(input, "test").collect();
+
+        assert_eq!(values.len(), 4);
+        assert_eq!(values[0].as_ref().unwrap(), &1);
+        assert_eq!(values[1].as_ref().unwrap(), &2);
+        assert!(values[2]
+            .as_ref()
+            .unwrap_err()
+            .to_string()
+            .starts_with("test:4:"));
+        assert_eq!(values[3].as_ref().unwrap(), &4);
+    }
+
+    #[derive(Debug)]
+    struct Range {
+        low: u32,
+        high: u32,
+    }
+
+    from_template!(Range, "{low}-{high}", { low, high });
+
+    #[test]
+    fn test_parse_records() {
+        let input = std::io::Cursor::new("1-2\n\n\n3-4\n\n5-x\n");
+        let err = parse_records::<_, Range>(input, "test").unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((err.line_number, err.column), (6, Some(3)));
+
+        let input = std::io::Cursor::new("1-2\n\n3-4");
+        let ranges: Vec<Range> = parse_records(input, "test").unwrap();
+        assert_eq!(ranges.len(), 2);
+        assert_eq!((ranges[1].low, ranges[1].high), (3, 4));
+    }
+}
@@ -0,0 +1,224 @@
+use crate::{CoordinateValue, Matrix, Point};
+
+const CARDINAL_OFFSETS: [(i64, i64); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+const DIAGONAL_OFFSETS: [(i64, i64); 4] = [(-1, -1), (1, -1), (-1, 1), (1, 1)];
+
+const ALL_OFFSETS: [(i64, i64); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+// Axial hex neighbours, treating x as q and y as r.
+const HEX_OFFSETS: [(i64, i64); 6] = [(1, 0), (-1, 0), (0, -1), (0, 1), (1, -1), (-1, 1)];
+
+/// How coordinates that fall off the edge of a `Matrix` are handled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Topology {
+    /// Coordinates outside the matrix don't exist.
+    Bounded,
+    /// The left and right edges are joined; the top and bottom are not.
+    WrapHorizontal,
+    /// Both pairs of opposite edges are joined.
+    Toroidal,
+}
+
+/// The set of offsets considered neighbours of a cell.
+#[derive(Clone, Copy, Debug)]
+pub enum Stencil<'a> {
+    Cardinal,
+    Diagonal,
+    All,
+    /// Axial hex neighbours for a matrix storing (q, r) as (x, y).
+    Hex,
+    Custom(&'a [(i64, i64)]),
+}
+
+impl<'a> Stencil<'a> {
+    pub fn offsets(&self) -> &'a [(i64, i64)] {
+        match self {
+            Stencil::Cardinal => &CARDINAL_OFFSETS,
+            Stencil::Diagonal => &DIAGONAL_OFFSETS,
+            Stencil::All => &ALL_OFFSETS,
+            Stencil::Hex => &HEX_OFFSETS,
+            Stencil::Custom(offsets) => offsets,
+        }
+    }
+}
+
+/// The neighbours of a cell. Each cell is yielded at most once and never the cell itself,
+/// even when wrapping around a matrix narrower than the stencil brings offsets together.
+pub struct Neighbors<'a, T: Copy> {
+    matrix: &'a Matrix<T>,
+    x: i64,
+    y: i64,
+    topology: Topology,
+    offsets: &'a [(i64, i64)],
+    index: usize,
+}
+
+impl<'a, T: Copy> Neighbors<'a, T> {
+    fn resolve(&self, (dx, dy): (i64, i64)) -> Option<CoordinateValue<T>> {
+        self.matrix
+            .get_with_topology(self.x + dx, self.y + dy, self.topology)
+    }
+}
+
+impl<'a, T: Copy> Iterator for Neighbors<'a, T> {
+    type Item = CoordinateValue<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.offsets.len() {
+            let index = self.index;
+            self.index += 1;
+
+            let neighbor = match self.resolve(self.offsets[index]) {
+                Some(neighbor) => neighbor,
+                None => continue,
+            };
+
+            let coordinates = &neighbor.coordinates;
+            let is_origin = coordinates.x as i64 == self.x && coordinates.y as i64 == self.y;
+            let seen = self.offsets[..index].iter().any(|offset| {
+                self.resolve(*offset)
+                    .is_some_and(|earlier| earlier.coordinates == *coordinates)
+            });
+            if !is_origin && !seen {
+                return Some(neighbor);
+            }
+        }
+
+        None
+    }
+}
+
+fn wrap(value: i64, size: usize) -> Option<usize> {
+    if size == 0 {
+        return None;
+    }
+
+    Some(value.rem_euclid(size as i64) as usize)
+}
+
+fn bound(value: i64, size: usize) -> Option<usize> {
+    if value < 0 || value >= size as i64 {
+        return None;
+    }
+
+    Some(value as usize)
+}
+
+impl<T: Copy> Matrix<T> {
+    /// Looks up a possibly out-of-range coordinate, resolving it according to `topology`.
+    pub fn get_with_topology(
+        &self,
+        x: i64,
+        y: i64,
+        topology: Topology,
+    ) -> Option<CoordinateValue<T>> {
+        let (x, y) = match topology {
+            Topology::Bounded => (bound(x, self.width)?, bound(y, self.height)?),
+            Topology::WrapHorizontal => (wrap(x, self.width)?, bound(y, self.height)?),
+            Topology::Toroidal => (wrap(x, self.width)?, wrap(y, self.height)?),
+        };
+
+        self.get(x, y).map(|value| CoordinateValue {
+            coordinates: Point::new(x, y),
+            value,
+        })
+    }
+
+    pub fn neighbors<'a>(
+        &'a self,
+        x: usize,
+        y: usize,
+        topology: Topology,
+        stencil: Stencil<'a>,
+    ) -> Neighbors<'a, T> {
+        Neighbors {
+            matrix: self,
+            x: x as i64,
+            y: y as i64,
+            topology,
+            offsets: stencil.offsets(),
+            index: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix() -> Matrix<u32> {
+        Matrix {
+            data: vec![vec![1, 2, 3], vec![4, 5, 6]],
+            width: 3,
+            height: 2,
+        }
+    }
+
+    fn values(neighbors: Neighbors<u32>) -> Vec<u32> {
+        neighbors.map(|neighbor| neighbor.value).collect()
+    }
+
+    #[test]
+    fn test_bounded_corner() {
+        let matrix = matrix();
+
+        assert_eq!(
+            values(matrix.neighbors(0, 0, Topology::Bounded, Stencil::All)),
+            vec![2, 4, 5]
+        );
+        assert_eq!(
+            values(matrix.neighbors(0, 0, Topology::Bounded, Stencil::Cardinal)),
+            vec![2, 4]
+        );
+    }
+
+    #[test]
+    fn test_wrapping() {
+        let matrix = matrix();
+
+        assert_eq!(
+            values(matrix.neighbors(0, 0, Topology::WrapHorizontal, Stencil::Cardinal)),
+            vec![3, 2, 4]
+        );
+        assert_eq!(
+            values(matrix.neighbors(0, 0, Topology::Toroidal, Stencil::Custom(&[(-1, -1)]))),
+            vec![6]
+        );
+    }
+    #[test]
+    fn test_wrapping_narrow() {
+        let matrix = Matrix {
+            data: vec![vec![1], vec![2], vec![3]],
+            width: 1,
+            height: 3,
+        };
+        assert_eq!(
+            values(matrix.neighbors(0, 1, Topology::Toroidal, Stencil::All)),
+            vec![1, 3]
+        );
+
+        let matrix = Matrix {
+            data: vec![vec![1, 2], vec![3, 4]],
+            width: 2,
+            height: 2,
+        };
+        assert_eq!(
+            values(matrix.neighbors(0, 0, Topology::WrapHorizontal, Stencil::Cardinal)),
+            vec![2, 3]
+        );
+        assert_eq!(
+            values(matrix.neighbors(0, 0, Topology::Toroidal, Stencil::All)),
+            vec![4, 3, 2]
+        );
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+
+/// The integer types a `RangeSet` can hold.
+pub trait Integer: Copy + Ord {
+    fn successor(self) -> Option<Self>;
+
+    fn predecessor(self) -> Option<Self>;
+
+    /// The number of values in `start..=end`, which must not be empty.
+    fn width(start: Self, end: Self) -> u128;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                fn successor(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+
+                fn predecessor(self) -> Option<Self> {
+                    self.checked_sub(1)
+                }
+
+                fn width(start: Self, end: Self) -> u128 {
+                    (end as i128 - start as i128) as u128 + 1
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+/// A set of integers stored as inclusive ranges, kept merged so that no two overlap or touch.
+/// Only `std` is used, so this file can be copied into any year's crate as is.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RangeSet<T: Integer> {
+    /// Inclusive end of each range, keyed by its start.
+    ranges: BTreeMap<T, T>,
+}
+
+impl<T: Integer> RangeSet<T> {
+    pub fn new() -> Self {
+        Self {
+            ranges: BTreeMap::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The number of disjoint ranges, not the number of values.
+    pub fn range_count(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// The number of values in the set.
+    pub fn covered(&self) -> u128 {
+        self.ranges
+            .iter()
+            .map(|(start, end)| T::width(*start, *end))
+            .sum()
+    }
+
+    pub fn ranges(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.iter().map(|(start, end)| *start..=*end)
+    }
+
+    /// The stored range holding `value`, if any.
+    fn range_containing(&self, value: T) -> Option<(T, T)> {
+        self.ranges
+            .range(..=value)
+            .next_back()
+            .filter(|(_, end)| **end >= value)
+            .map(|(start, end)| (*start, *end))
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.range_containing(value).is_some()
+    }
+
+    pub fn insert(&mut self, range: RangeInclusive<T>) {
+        let (mut start, mut end) = range.into_inner();
+        if start > end {
+            return;
+        }
+
+        if let Some((&before_start, &before_end)) = self.ranges.range(..=start).next_back() {
+            if before_end >= start || before_end.successor() == Some(start) {
+                self.ranges.remove(&before_start);
+                start = before_start;
+                end = std::cmp::max(end, before_end);
+            }
+        }
+
+        // Anything starting inside the new range, or just after it, is absorbed.
+        let limit = end.successor().unwrap_or(end);
+        let absorbed: Vec<(T, T)> = self
+            .ranges
+            .range(start..=limit)
+            .map(|(start, end)| (*start, *end))
+            .collect();
+        for (absorbed_start, absorbed_end) in absorbed {
+            self.ranges.remove(&absorbed_start);
+            end = std::cmp::max(end, absorbed_end);
+        }
+
+        self.ranges.insert(start, end);
+    }
+
+    pub fn remove(&mut self, range: RangeInclusive<T>) {
+        let (start, end) = range.into_inner();
+        if start > end {
+            return;
+        }
+
+        let mut affected: Vec<(T, T)> = self
+            .ranges
+            .range(start..=end)
+            .map(|(start, end)| (*start, *end))
+            .collect();
+        if let Some((&before_start, &before_end)) = self.ranges.range(..start).next_back() {
+            if before_end >= start {
+                affected.push((before_start, before_end));
+            }
+        }
+
+        for (affected_start, affected_end) in affected {
+            self.ranges.remove(&affected_start);
+
+            if affected_start < start {
+                if let Some(before) = start.predecessor() {
+                    self.ranges.insert(affected_start, before);
+                }
+            }
+            if affected_end > end {
+                if let Some(after) = end.successor() {
+                    self.ranges.insert(after, affected_end);
+                }
+            }
+        }
+    }
+
+    /// Adds every range of `other` to this set.
+    pub fn merge(&mut self, other: &RangeSet<T>) {
+        for range in other.ranges() {
+            self.insert(range);
+        }
+    }
+
+    pub fn union(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut union = self.clone();
+        union.merge(other);
+        union
+    }
+
+    pub fn difference(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut difference = self.clone();
+        for range in other.ranges() {
+            difference.remove(range);
+        }
+        difference
+    }
+
+    pub fn intersection(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        self.difference(&self.difference(other))
+    }
+
+    pub fn is_superset(&self, other: &RangeSet<T>) -> bool {
+        other.difference(self).is_empty()
+    }
+
+    pub fn overlaps(&self, other: &RangeSet<T>) -> bool {
+        !self.intersection(other).is_empty()
+    }
+
+    /// The ranges within `bounds` that are not in the set.
+    pub fn gaps(&self, bounds: RangeInclusive<T>) -> Vec<RangeInclusive<T>> {
+        RangeSet::from(bounds).difference(self).ranges().collect()
+    }
+
+    /// The smallest value at or after `from` that is not in the set.
+    pub fn first_uncovered(&self, from: T) -> Option<T> {
+        match self.range_containing(from) {
+            Some((_, end)) => end.successor(),
+            None => Some(from),
+        }
+    }
+}
+
+impl<T: Integer> From<RangeInclusive<T>> for RangeSet<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        let mut set = RangeSet::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl<T: Integer> std::iter::FromIterator<RangeInclusive<T>> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(ranges: I) -> Self {
+        let mut set = RangeSet::new();
+        for range in ranges {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut set: RangeSet<i32> = vec![5..=8, 0..=2, 4..=7].into_iter().collect();
+        assert_eq!(set.ranges().collect::<Vec<_>>(), vec![0..=2, 4..=8]);
+
+        set.insert(3..=3);
+        assert_eq!(set.ranges().collect::<Vec<_>>(), vec![0..=8]);
+
+        set.remove(2..=4);
+        set.remove(8..=20);
+        assert_eq!(set.ranges().collect::<Vec<_>>(), vec![0..=1, 5..=7]);
+        assert_eq!(set.covered(), 5);
+        assert!(set.contains(6));
+        assert!(!set.contains(3));
+
+        let mut full = RangeSet::from(0..=u8::MAX);
+        full.remove(0..=0);
+        assert_eq!(full.first_uncovered(0), Some(0));
+        assert_eq!(full.first_uncovered(1), None);
+    }
+
+    #[test]
+    fn test_blacklist() {
+        let blocked: RangeSet<u32> = vec![5..=8, 0..=2, 4..=7].into_iter().collect();
+
+        assert_eq!(blocked.first_uncovered(0), Some(3));
+        assert_eq!(blocked.gaps(0..=9), vec![3..=3, 9..=9]);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a: RangeSet<i64> = vec![0..=10, 20..=30].into_iter().collect();
+        let b: RangeSet<i64> = vec![5..=25].into_iter().collect();
+
+        assert_eq!(
+            a.intersection(&b).ranges().collect::<Vec<_>>(),
+            vec![5..=10, 20..=25]
+        );
+        assert_eq!(
+            a.difference(&b).ranges().collect::<Vec<_>>(),
+            vec![0..=4, 26..=30]
+        );
+        assert_eq!(a.union(&b).ranges().collect::<Vec<_>>(), vec![0..=30]);
+        assert!(a.union(&b).is_superset(&a));
+        assert!(!a.is_superset(&b));
+        assert!(a.overlaps(&b));
+    }
+}
@@ -0,0 +1,413 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use anyhow::{Error, Result};
+
+use crate::template::Template;
+
+fn cross(a: (i64, i64), b: (i64, i64)) -> i64 {
+    a.0 * b.1 - a.1 * b.0
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// A straight line between two lattice points, including both ends.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Segment {
+    pub start: (i64, i64),
+    pub end: (i64, i64),
+}
+
+/// Where two segments meet. Only lattice points count, so crossing lines may not intersect.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Intersection {
+    None,
+    Point((i64, i64)),
+    /// The segments are collinear and share every lattice point of this segment.
+    Overlap(Segment),
+}
+
+impl Intersection {
+    pub fn points(&self) -> Vec<(i64, i64)> {
+        match self {
+            Intersection::None => Vec::new(),
+            Intersection::Point(point) => vec![*point],
+            Intersection::Overlap(segment) => segment.lattice_points(),
+        }
+    }
+}
+
+impl Segment {
+    pub fn new(start: (i64, i64), end: (i64, i64)) -> Self {
+        Self { start, end }
+    }
+
+    fn delta(&self) -> (i64, i64) {
+        (self.end.0 - self.start.0, self.end.1 - self.start.1)
+    }
+
+    /// The smallest lattice step along the segment, or (0, 0) for a single point.
+    fn primitive_step(&self) -> ((i64, i64), i64) {
+        let (dx, dy) = self.delta();
+        let divisor = gcd(dx, dy);
+        if divisor == 0 {
+            return ((0, 0), 0);
+        }
+
+        ((dx / divisor, dy / divisor), divisor)
+    }
+
+    pub fn is_horizontal(&self) -> bool {
+        self.start.1 == self.end.1
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        self.start.0 == self.end.0
+    }
+
+    pub fn is_axis_aligned(&self) -> bool {
+        self.is_horizontal() || self.is_vertical()
+    }
+
+    /// True for segments at exactly 45°.
+    pub fn is_diagonal(&self) -> bool {
+        let (dx, dy) = self.delta();
+        dx != 0 && dx.abs() == dy.abs()
+    }
+
+    /// The number of king moves from one end to the other.
+    pub fn steps(&self) -> i64 {
+        let (dx, dy) = self.delta();
+        std::cmp::max(dx.abs(), dy.abs())
+    }
+
+    /// The cells covered when drawing the segment, from start to end. Axis-aligned and
+    /// diagonal segments are exact; anything else follows Bresenham's algorithm.
+    pub fn points(&self) -> Points {
+        let (dx, dy) = self.delta();
+
+        Points {
+            current: self.start,
+            end: self.end,
+            step: (dx.signum(), dy.signum()),
+            dx: dx.abs(),
+            dy: -dy.abs(),
+            error: dx.abs() - dy.abs(),
+            done: false,
+        }
+    }
+
+    /// The points lying exactly on the segment. These match `points` for axis-aligned and
+    /// diagonal segments.
+    pub fn lattice_points(&self) -> Vec<(i64, i64)> {
+        let (step, count) = self.primitive_step();
+        (0..=count)
+            .map(|k| (self.start.0 + step.0 * k, self.start.1 + step.1 * k))
+            .collect()
+    }
+
+    pub fn contains(&self, point: (i64, i64)) -> bool {
+        let offset = (point.0 - self.start.0, point.1 - self.start.1);
+        cross(offset, self.delta()) == 0
+            && point.0 >= std::cmp::min(self.start.0, self.end.0)
+            && point.0 <= std::cmp::max(self.start.0, self.end.0)
+            && point.1 >= std::cmp::min(self.start.1, self.end.1)
+            && point.1 <= std::cmp::max(self.start.1, self.end.1)
+    }
+
+    /// King moves from the start to `point`, if it lies on the segment.
+    pub fn steps_to(&self, point: (i64, i64)) -> Option<i64> {
+        if !self.contains(point) {
+            return None;
+        }
+
+        Some(Segment::new(self.start, point).steps())
+    }
+
+    /// The position of a collinear lattice point, in primitive steps from the start.
+    fn position_of(&self, point: (i64, i64), step: (i64, i64)) -> i64 {
+        if step.0 != 0 {
+            (point.0 - self.start.0) / step.0
+        } else {
+            (point.1 - self.start.1) / step.1
+        }
+    }
+
+    pub fn intersection(&self, other: &Segment) -> Intersection {
+        let (r, s) = (self.delta(), other.delta());
+
+        if r == (0, 0) || s == (0, 0) {
+            let (point, segment) = if r == (0, 0) {
+                (self.start, other)
+            } else {
+                (other.start, self)
+            };
+
+            return if segment.contains(point) {
+                Intersection::Point(point)
+            } else {
+                Intersection::None
+            };
+        }
+
+        let offset = (other.start.0 - self.start.0, other.start.1 - self.start.1);
+        let denominator = cross(r, s);
+
+        if denominator == 0 {
+            if cross(offset, r) != 0 {
+                return Intersection::None;
+            }
+
+            // Collinear: both segments' lattice points are whole steps along the same line.
+            let (step, count) = self.primitive_step();
+            let a = self.position_of(other.start, step);
+            let b = self.position_of(other.end, step);
+            let low = std::cmp::max(0, std::cmp::min(a, b));
+            let high = std::cmp::min(count, std::cmp::max(a, b));
+
+            let at = |k: i64| (self.start.0 + step.0 * k, self.start.1 + step.1 * k);
+            return match low.cmp(&high) {
+                std::cmp::Ordering::Greater => Intersection::None,
+                std::cmp::Ordering::Equal => Intersection::Point(at(low)),
+                std::cmp::Ordering::Less => Intersection::Overlap(Segment::new(at(low), at(high))),
+            };
+        }
+
+        let (mut t, mut u, mut denominator) = (cross(offset, s), cross(offset, r), denominator);
+        if denominator < 0 {
+            t = -t;
+            u = -u;
+            denominator = -denominator;
+        }
+
+        if t < 0 || t > denominator || u < 0 || u > denominator {
+            return Intersection::None;
+        }
+
+        if (r.0 * t) % denominator != 0 || (r.1 * t) % denominator != 0 {
+            return Intersection::None;
+        }
+
+        Intersection::Point((
+            self.start.0 + r.0 * t / denominator,
+            self.start.1 + r.1 * t / denominator,
+        ))
+    }
+}
+
+/// Parses "x1,y1 -> x2,y2".
+impl FromStr for Segment {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let captures = Template::new("{x1},{y1}->{x2},{y2}")?.captures(value)?;
+
+        Ok(Segment::new(
+            (captures.parse("x1")?, captures.parse("y1")?),
+            (captures.parse("x2")?, captures.parse("y2")?),
+        ))
+    }
+}
+
+pub struct Points {
+    current: (i64, i64),
+    end: (i64, i64),
+    step: (i64, i64),
+    dx: i64,
+    dy: i64,
+    error: i64,
+    done: bool,
+}
+
+impl Iterator for Points {
+    type Item = (i64, i64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let point = self.current;
+        if point == self.end {
+            self.done = true;
+            return Some(point);
+        }
+
+        let doubled = 2 * self.error;
+        if doubled >= self.dy {
+            self.error += self.dy;
+            self.current.0 += self.step.0;
+        }
+        if doubled <= self.dx {
+            self.error += self.dx;
+            self.current.1 += self.step.1;
+        }
+
+        Some(point)
+    }
+}
+
+/// How many of `segments` cover each cell.
+pub fn coverage<'a, I>(segments: I) -> HashMap<(i64, i64), usize>
+where
+    I: IntoIterator<Item = &'a Segment>,
+{
+    let mut counts = HashMap::new();
+    for segment in segments {
+        for point in segment.points() {
+            *counts.entry(point).or_insert(0) += 1;
+        }
+    }
+
+    counts
+}
+
+/// The number of cells covered by at least `minimum` of `segments`.
+pub fn count_overlaps<'a, I>(segments: I, minimum: usize) -> usize
+where
+    I: IntoIterator<Item = &'a Segment>,
+{
+    coverage(segments)
+        .values()
+        .filter(|count| **count >= minimum)
+        .count()
+}
+
+/// Connected segments walked in order, such as a wire or a rock formation.
+#[derive(Clone, Debug, Default)]
+pub struct Path {
+    pub segments: Vec<Segment>,
+}
+
+impl Path {
+    /// A path through each of `points` in turn.
+    pub fn through(points: &[(i64, i64)]) -> Self {
+        Self {
+            segments: points
+                .windows(2)
+                .map(|pair| Segment::new(pair[0], pair[1]))
+                .collect(),
+        }
+    }
+
+    /// A path from `start` following relative moves.
+    pub fn from_moves(start: (i64, i64), moves: &[(i64, i64)]) -> Self {
+        let mut points = vec![start];
+        for (dx, dy) in moves {
+            let (x, y) = points[points.len() - 1];
+            points.push((x + dx, y + dy));
+        }
+
+        Self::through(&points)
+    }
+
+    /// Total king moves along the whole path.
+    pub fn steps(&self) -> i64 {
+        self.segments.iter().map(|segment| segment.steps()).sum()
+    }
+
+    /// King moves along the path until `point` is first reached.
+    pub fn steps_to(&self, point: (i64, i64)) -> Option<i64> {
+        let mut walked = 0;
+        for segment in self.segments.iter() {
+            if let Some(steps) = segment.steps_to(point) {
+                return Some(walked + steps);
+            }
+            walked += segment.steps();
+        }
+
+        None
+    }
+
+    /// Every lattice point shared with `other`, without duplicates, in no particular order.
+    pub fn crossings(&self, other: &Path) -> Vec<(i64, i64)> {
+        let mut crossings = Vec::new();
+        for a in self.segments.iter() {
+            for b in other.segments.iter() {
+                crossings.extend(a.intersection(b).points());
+            }
+        }
+
+        crossings.sort_unstable();
+        crossings.dedup();
+        crossings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_points() {
+        let points: Vec<(i64, i64)> = Segment::new((9, 7), (7, 9)).points().collect();
+        assert_eq!(points, vec![(9, 7), (8, 8), (7, 9)]);
+
+        let points: Vec<(i64, i64)> = Segment::new((0, 0), (4, 1)).points().collect();
+        assert_eq!(points, vec![(0, 0), (1, 0), (2, 1), (3, 1), (4, 1)]);
+
+        let segments: Vec<Segment> = [
+            "0,9 -> 5,9",
+            "8,0 -> 0,8",
+            "9,4 -> 3,4",
+            "2,2 -> 2,1",
+            "7,0 -> 7,4",
+            "6,4 -> 2,0",
+            "0,9 -> 2,9",
+            "3,4 -> 1,4",
+            "0,0 -> 8,8",
+            "5,5 -> 8,2",
+        ]
+        .iter()
+        .map(|line| line.parse())
+        .collect::<Result<_>>()
+        .unwrap();
+        assert_eq!(count_overlaps(&segments, 2), 12);
+    }
+
+    #[test]
+    fn test_intersection() {
+        let horizontal = Segment::new((0, 2), (6, 2));
+        assert_eq!(
+            horizontal.intersection(&Segment::new((3, 0), (3, 5))),
+            Intersection::Point((3, 2))
+        );
+        assert_eq!(
+            horizontal.intersection(&Segment::new((4, 2), (9, 2))),
+            Intersection::Overlap(Segment::new((4, 2), (6, 2)))
+        );
+        assert_eq!(
+            horizontal.intersection(&Segment::new((6, 2), (6, 9))),
+            Intersection::Point((6, 2))
+        );
+        assert_eq!(
+            Segment::new((0, 0), (1, 1)).intersection(&Segment::new((0, 1), (1, 0))),
+            Intersection::None
+        );
+    }
+
+    #[test]
+    fn test_path_steps() {
+        let a = Path::from_moves((0, 0), &[(8, 0), (0, 5), (-5, 0), (0, -3)]);
+        let b = Path::from_moves((0, 0), &[(0, 7), (6, 0), (0, -4), (-4, 0)]);
+
+        let crossings: Vec<(i64, i64)> = a
+            .crossings(&b)
+            .into_iter()
+            .filter(|point| *point != (0, 0))
+            .collect();
+        assert_eq!(crossings, vec![(3, 3), (6, 5)]);
+
+        let fewest = crossings
+            .iter()
+            .map(|point| a.steps_to(*point).unwrap() + b.steps_to(*point).unwrap())
+            .min();
+        assert_eq!(fewest, Some(30));
+    }
+}
@@ -1,57 +1,37 @@
+use std::str::FromStr;
+
 use anyhow::Result;
-use aoc_2022::Args;
+use aoc_2022::{read_records, Args};
 use structopt::StructOpt;
 
-fn main() -> Result<()> {
-    let args = Args::from_args();
-
-    let mut max_calories = 0;
-    let mut running_count = 0;
-
-    for line in std::fs::read_to_string(&args.filename)?.lines() {
-        if line.is_empty() {
-            if running_count > max_calories {
-                max_calories = running_count;
-            }
+/// The food one elf carries, one item per line.
+struct Elf {
+    calories: usize,
+}
 
-            running_count = 0;
+impl FromStr for Elf {
+    type Err = anyhow::Error;
 
-            continue;
+    fn from_str(record: &str) -> Result<Self> {
+        let mut calories = 0;
+        for line in record.lines() {
+            calories += line.parse::<usize>()?;
         }
 
-        let calories: usize = line.parse()?;
-        running_count += calories;
-    }
-
-    if running_count > max_calories {
-        max_calories = running_count;
+        Ok(Elf { calories })
     }
+}
 
-    println!("{}", max_calories);
-
-    let mut calories = Vec::new();
-    let mut running_count = 0;
-
-    for line in std::fs::read_to_string(&args.filename)?.lines() {
-        if line.is_empty() {
-            calories.push(running_count);
-            running_count = 0;
-
-            continue;
-        }
-
-        let calories: usize = line.parse()?;
-        running_count += calories;
-    }
+fn main() -> Result<()> {
+    let args = Args::from_args();
 
-    calories.push(running_count);
+    let elves: Vec<Elf> = read_records(&args.filename)?;
 
+    let mut calories: Vec<usize> = elves.iter().map(|elf| elf.calories).collect();
     calories.sort();
 
-    println!(
-        "{}",
-        calories.pop().unwrap() + calories.pop().unwrap() + calories.pop().unwrap()
-    );
+    println!("{}", calories.last().copied().unwrap_or(0));
+    println!("{}", calories.iter().rev().take(3).sum::<usize>());
 
     Ok(())
 }
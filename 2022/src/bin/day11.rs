@@ -1,10 +1,9 @@
 use std::collections::{HashMap, VecDeque};
 use std::fmt;
-use std::iter::Peekable;
-use std::str::{FromStr, Lines};
+use std::str::FromStr;
 
 use anyhow::{anyhow, Result};
-use aoc_2022::Args;
+use aoc_2022::{read_records, Args};
 use structopt::StructOpt;
 
 #[derive(Debug)]
@@ -51,17 +50,21 @@ impl Monkey {
     fn add_item(&mut self, item: i64) {
         self.items.push_back(item);
     }
+}
 
-    fn parse(lines: &mut Peekable<Lines>) -> Result<Self> {
-        let line = {
-            let line = lines.next().unwrap();
-            if line.is_empty() {
-                lines.next().unwrap()
-            } else {
-                line
-            }
+impl FromStr for Monkey {
+    type Err = anyhow::Error;
+
+    fn from_str(record: &str) -> Result<Self> {
+        let mut lines = record.lines();
+        let mut next_line = || {
+            lines
+                .next()
+                .ok_or_else(|| anyhow!("Monkey \"{}\" is missing lines", record))
         };
 
+        let line = next_line()?;
+
         let monkey_id_parts = line.split_whitespace().collect::<Vec<&str>>();
         let monkey_id_str = monkey_id_parts[1];
         let monkey_id: usize = monkey_id_str
@@ -70,13 +73,13 @@ impl Monkey {
             .collect::<String>()
             .parse()?;
 
-        let line = lines.next().unwrap();
+        let line = next_line()?;
         let items: VecDeque<i64> = line.split(": ").collect::<Vec<&str>>()[1]
             .split(", ")
             .map(|i| i.parse::<i64>().unwrap())
             .collect();
 
-        let line = lines.next().unwrap();
+        let line = next_line()?;
         let right_part = line.split(" = ").collect::<Vec<&str>>()[1];
         let parts: Vec<&str> = right_part.split_whitespace().collect();
         let left: Operand = parts[0].parse()?;
@@ -89,15 +92,15 @@ impl Monkey {
             right,
         };
 
-        let line = lines.next().unwrap();
+        let line = next_line()?;
         let parts: Vec<&str> = line.split_whitespace().collect();
         let divisor: i64 = parts[parts.len() - 1].parse()?;
 
-        let line = lines.next().unwrap();
+        let line = next_line()?;
         let parts: Vec<&str> = line.split_whitespace().collect();
         let true_path: usize = parts[parts.len() - 1].parse()?;
 
-        let line = lines.next().unwrap();
+        let line = next_line()?;
         let parts: Vec<&str> = line.split_whitespace().collect();
         let false_path: usize = parts[parts.len() - 1].parse()?;
 
@@ -200,16 +203,8 @@ fn main() -> Result<()> {
     let args = Args::from_args();
 
     let mut monkeys = HashMap::new();
-    let data = std::fs::read_to_string(&args.filename)?;
-    let mut lines = data.lines().peekable();
-
-    loop {
-        if lines.peek().is_some() {
-            let monkey = Monkey::parse(&mut lines)?;
-            monkeys.insert(monkey.id, monkey);
-        } else {
-            break;
-        }
+    for monkey in read_records::<Monkey>(&args.filename)? {
+        monkeys.insert(monkey.id, monkey);
     }
 
     for _round in 0..20 {
@@ -1,167 +1,318 @@
-use std::collections::HashMap;
 use std::fmt;
 
 use anyhow::Result;
-use aoc_2022::{Args, Point};
+use aoc_2022::image::{Image, Raster, Rgb};
+use aoc_2022::segment::{Path, Segment};
+use aoc_2022::sparse_grid::{Bounds, SparseGrid};
+use aoc_2022::tile::Tile;
+use aoc_2022::tile_enum;
+use aoc_2022::Point;
 use structopt::StructOpt;
 
-#[derive(Clone, Debug)]
-enum Space {
-    Empty,
-    Rock,
-    Sand,
+#[derive(StructOpt)]
+struct Args {
+    filename: String,
+
+    /// Save a picture of the part 1 sand pile (.ppm, .png or .svg)
+    #[structopt(long)]
+    image: Option<String>,
+
+    /// Where sand enters the cave, as "x,y"
+    #[structopt(long, default_value = "500,0")]
+    source: Point,
+
+    /// Directory to write a .png of the pile into as grains come to rest
+    #[structopt(long)]
+    frames: Option<String>,
+
+    /// Number of grains placed between frames
+    #[structopt(long, default_value = "1")]
+    frame_interval: usize,
 }
 
-impl fmt::Display for Space {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Space::Empty => ".",
-                Space::Rock => "#",
-                Space::Sand => "O",
-            }
-        )
+tile_enum! {
+    enum Space {
+        Empty = '.',
+        Rock = '#',
+        Sand = 'O',
+    }
+}
+
+fn palette(space: &Space) -> Rgb {
+    match space {
+        Space::Empty => Rgb(20, 20, 40),
+        Space::Rock => Rgb(110, 110, 110),
+        Space::Sand => Rgb(230, 200, 120),
     }
 }
 
 #[derive(Debug)]
 struct Matrix {
-    pub data: HashMap<Point, Space>,
-    pub max_y: usize,
+    pub data: SparseGrid<Space>,
+}
+
+impl Matrix {
+    fn new() -> Self {
+        Matrix {
+            data: SparseGrid::new(Space::Empty),
+        }
+    }
+
+    fn max_y(&self) -> i64 {
+        self.data.bounds().map(|bounds| bounds.max_y).unwrap_or(0)
+    }
+
+    fn get(&self, x: i64, y: i64) -> &Space {
+        self.data.get(x, y)
+    }
+
+    fn add_line(&mut self, segment: &Segment, space: Space) {
+        for (x, y) in segment.points() {
+            self.data.set(x, y, space);
+        }
+    }
+
+    fn add_path(&mut self, path: &Path, space: Space) {
+        for segment in path.segments.iter() {
+            self.add_line(segment, space);
+        }
+    }
+}
+
+impl fmt::Display for Matrix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.data.render(Space::to_char))
+    }
+}
+
+/// What lies below the lowest rock.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Bottom {
+    /// Sand falling past the lowest rock never stops.
+    Abyss,
+    /// An endless rock floor two rows below the lowest rock.
+    Floor,
 }
 
 #[derive(Debug)]
 enum FallResult {
     Placed,
     FallingForever,
+    SourceBlocked,
 }
 
-impl Matrix {
-    fn new() -> Self {
-        Matrix {
-            data: HashMap::new(),
-            max_y: 0,
+struct Simulation {
+    matrix: Matrix,
+    source: (i64, i64),
+    bottom: Bottom,
+    max_rock_y: i64,
+    /// The cells the previous grain fell through. The next grain follows the same route
+    /// until the first cell that has since filled up, so it can start from just above there.
+    path: Vec<(i64, i64)>,
+}
+
+impl Simulation {
+    fn new(matrix: Matrix, source: (i64, i64), bottom: Bottom) -> Self {
+        let max_rock_y = matrix.max_y();
+
+        Simulation {
+            matrix,
+            source,
+            bottom,
+            max_rock_y,
+            path: Vec::new(),
         }
     }
 
-    fn get(&self, point: &Point) -> Space {
-        self.data.get(point).cloned().unwrap_or(Space::Empty)
+    fn floor_y(&self) -> i64 {
+        self.max_rock_y + 2
     }
 
-    fn add_point(&mut self, point: Point, space: Space) {
-        self.max_y = std::cmp::max(self.max_y, point.y);
-        self.data.insert(point, space);
+    fn get(&self, x: i64, y: i64) -> Space {
+        if self.bottom == Bottom::Floor && y >= self.floor_y() {
+            return Space::Rock;
+        }
+
+        *self.matrix.get(x, y)
     }
 
-    fn add_line(&mut self, start: Point, end: Point, space: Space) {
-        for i in std::cmp::min(start.y, end.y)..=std::cmp::max(start.y, end.y) {
-            for j in std::cmp::min(start.x, end.x)..=std::cmp::max(start.x, end.x) {
-                self.add_point(Point::new(j, i), space.clone());
-            }
-        }
+    fn is_empty(&self, x: i64, y: i64) -> bool {
+        matches!(self.get(x, y), Space::Empty)
     }
 
-    fn add_sand(&mut self, start: Point) -> FallResult {
-        let mut current_point = start;
-        loop {
-            if current_point.y > self.max_y {
-                return FallResult::FallingForever;
+    fn add_sand(&mut self) -> FallResult {
+        while let Some((x, y)) = self.path.last() {
+            if self.is_empty(*x, *y) {
+                break;
             }
+            self.path.pop();
+        }
 
-            let down_point = Point::new(current_point.x, current_point.y + 1);
-            if let Space::Empty = self.get(&down_point) {
-                current_point = down_point;
-                continue;
+        if self.path.is_empty() {
+            if !self.is_empty(self.source.0, self.source.1) {
+                return FallResult::SourceBlocked;
             }
+            self.path.push(self.source);
+        }
 
-            let down_left_point = Point::new(current_point.x - 1, current_point.y + 1);
-            if let Space::Empty = self.get(&down_left_point) {
-                current_point = down_left_point;
-                continue;
+        let (mut x, mut y) = self.path[self.path.len() - 1];
+        loop {
+            if self.bottom == Bottom::Abyss && y > self.max_rock_y {
+                return FallResult::FallingForever;
             }
 
-            let down_right_point = Point::new(current_point.x + 1, current_point.y + 1);
-            if let Space::Empty = self.get(&down_right_point) {
-                current_point = down_right_point;
+            if let Some(next_x) = [x, x - 1, x + 1]
+                .into_iter()
+                .find(|next_x| self.is_empty(*next_x, y + 1))
+            {
+                x = next_x;
+                y += 1;
+                self.path.push((x, y));
                 continue;
             }
 
-            self.add_point(current_point, Space::Sand);
+            self.matrix.data.set(x, y, Space::Sand);
             return FallResult::Placed;
         }
     }
 
-    fn render(&self, top_left: Point, bottom_right: Point) {
-        for i in top_left.y..=bottom_right.y {
-            for j in top_left.x..=bottom_right.x {
-                print!("{}", self.get(&Point::new(j, i)));
-            }
-            println!("");
+    /// Adds sand until a grain falls forever or the source is blocked, calling `on_placed`
+    /// with the running count after each grain comes to rest. Returns the number placed.
+    fn run<F>(&mut self, mut on_placed: F) -> Result<usize>
+    where
+        F: FnMut(&Simulation, usize) -> Result<()>,
+    {
+        let mut count = 0;
+        while let FallResult::Placed = self.add_sand() {
+            count += 1;
+            on_placed(self, count)?;
         }
-    }
-}
 
-fn main() -> Result<()> {
-    let args = Args::from_args();
+        Ok(count)
+    }
 
-    let mut matrix = Matrix::new();
+    /// A box large enough for every grain that can ever come to rest, so that frames of the
+    /// same run all line up.
+    fn viewport(&self) -> Bounds {
+        let mut viewport = self.matrix.data.bounds().unwrap_or(Bounds {
+            min_x: self.source.0,
+            min_y: self.source.1,
+            max_x: self.source.0,
+            max_y: self.source.1,
+        });
+        viewport.min_x = std::cmp::min(viewport.min_x, self.source.0);
+        viewport.max_x = std::cmp::max(viewport.max_x, self.source.0);
+        viewport.min_y = std::cmp::min(viewport.min_y, self.source.1);
 
-    for line in std::fs::read_to_string(&args.filename)?.lines() {
-        let mut points = Vec::new();
-        let parts: Vec<&str> = line.split(" -> ").collect();
-        for part in parts {
-            points.push(part.parse::<Point>()?);
+        if self.bottom == Bottom::Floor {
+            // The pile can spread at most one column per row as it falls.
+            let spread = self.floor_y() - self.source.1;
+            viewport.min_x = std::cmp::min(viewport.min_x, self.source.0 - spread);
+            viewport.max_x = std::cmp::max(viewport.max_x, self.source.0 + spread);
+            viewport.max_y = self.floor_y();
         }
 
-        for i in 0..points.len() - 1 {
-            matrix.add_line(points[i].clone(), points[i + 1].clone(), Space::Rock);
-        }
+        viewport
     }
+}
 
-    let mut count = 0;
-    loop {
-        if let FallResult::FallingForever = matrix.add_sand(Point::new(500, 0)) {
-            break;
-        }
+/// The simulation as seen through a fixed viewport.
+struct Frame<'a> {
+    simulation: &'a Simulation,
+    viewport: Bounds,
+}
+
+impl<'a> Raster for Frame<'a> {
+    type Tile = Space;
 
-        count += 1;
+    fn width(&self) -> usize {
+        self.viewport.width()
     }
 
-    println!("{}", count);
+    fn height(&self) -> usize {
+        self.viewport.height()
+    }
 
-    let mut matrix = Matrix::new();
+    fn tile(&self, x: usize, y: usize) -> Space {
+        self.simulation.get(
+            self.viewport.min_x + x as i64,
+            self.viewport.min_y + y as i64,
+        )
+    }
+}
 
-    for line in std::fs::read_to_string(&args.filename)?.lines() {
+fn read_paths(filename: &str) -> Result<Vec<Path>> {
+    let mut paths = Vec::new();
+    for line in std::fs::read_to_string(filename)?.lines() {
         let mut points = Vec::new();
-        let parts: Vec<&str> = line.split(" -> ").collect();
-        for part in parts {
-            points.push(part.parse::<Point>()?);
+        for part in line.split(" -> ") {
+            let point = part.parse::<Point>()?;
+            points.push((point.x as i64, point.y as i64));
         }
 
-        for i in 0..points.len() - 1 {
-            matrix.add_line(points[i].clone(), points[i + 1].clone(), Space::Rock);
-        }
+        paths.push(Path::through(&points));
     }
 
-    let line_y = matrix.max_y + 2;
-    matrix.add_line(
-        Point::new(0, line_y),
-        Point::new(100000, line_y),
-        Space::Rock,
-    );
+    Ok(paths)
+}
 
-    let mut count = 0;
-    loop {
-        matrix.add_sand(Point::new(500, 0));
-        count += 1;
-        if let Space::Sand = matrix.get(&Point::new(500, 0)) {
-            break;
-        }
+fn simulate(args: &Args, paths: &[Path], bottom: Bottom, part: usize) -> Result<Simulation> {
+    let mut matrix = Matrix::new();
+    for path in paths.iter() {
+        matrix.add_path(path, Space::Rock);
     }
 
+    let source = (args.source.x as i64, args.source.y as i64);
+    let mut simulation = Simulation::new(matrix, source, bottom);
+    let viewport = simulation.viewport();
+
+    if let Some(directory) = &args.frames {
+        std::fs::create_dir_all(directory)?;
+    }
+
+    let count = simulation.run(|simulation, count| {
+        let directory = match &args.frames {
+            Some(directory) if count % args.frame_interval.max(1) == 0 => directory,
+            _ => return Ok(()),
+        };
+
+        Image::new(
+            &Frame {
+                simulation,
+                viewport,
+            },
+            palette,
+        )
+        .scale(4)
+        .save(format!("{}/part{}_{:06}.png", directory, part, count))
+    })?;
+
     println!("{}", count);
 
+    Ok(simulation)
+}
+
+fn main() -> Result<()> {
+    let args = Args::from_args();
+
+    let paths = read_paths(&args.filename)?;
+
+    let simulation = simulate(&args, &paths, Bottom::Abyss, 1)?;
+
+    if let Some(path) = &args.image {
+        let viewport = simulation.viewport();
+        Image::new(
+            &Frame {
+                simulation: &simulation,
+                viewport,
+            },
+            palette,
+        )
+        .scale(4)
+        .save(path)?;
+    }
+
+    simulate(&args, &paths, Bottom::Floor, 2)?;
+
     Ok(())
 }
@@ -2,6 +2,7 @@ use std::fs::read_to_string;
 use std::str::FromStr;
 
 use anyhow::{anyhow, Result};
+use aoc_2022::template::Template;
 use aoc_2022::Args;
 use structopt::StructOpt;
 
@@ -21,20 +22,18 @@ impl FromStr for Movement {
     type Err = anyhow::Error;
 
     fn from_str(value: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<&str> = value.split(' ').collect();
-
-        let quantity: usize = parts
-            .get(1)
-            .ok_or_else(|| anyhow!("Missing quantity in movement \"{}\"", value))?
-            .parse()?;
-        let source: usize = parts
-            .get(3)
-            .ok_or_else(|| anyhow!("Missing source stack in movement \"{}\"", value))?
-            .parse()?;
-        let destination: usize = parts
-            .get(5)
-            .ok_or_else(|| anyhow!("Missing destination stack in movement \"{}\"", value))?
-            .parse()?;
+        let captures =
+            Template::new("move {quantity} from {source} to {destination}")?.captures(value)?;
+
+        let quantity: usize = captures.parse("quantity")?;
+        let source: usize = captures.parse("source")?;
+        let destination: usize = captures.parse("destination")?;
+        if source == 0 || destination == 0 {
+            return Err(anyhow!(
+                "Stacks are numbered from 1 in movement \"{}\"",
+                value
+            ));
+        }
 
         Ok(Movement {
             quantity,
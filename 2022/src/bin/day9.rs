@@ -2,15 +2,45 @@ use std::collections::HashSet;
 use std::str::FromStr;
 
 use anyhow::{anyhow, Result};
-use aoc_2022::Args;
+use aoc_2022::from_template;
+use aoc_2022::sparse_grid::SparseGrid;
+use aoc_2022::SignedPoint;
 use structopt::StructOpt;
 
+#[derive(StructOpt)]
+struct Args {
+    filename: String,
+
+    /// Print the rope after every move
+    #[structopt(long)]
+    show: bool,
+}
+
 #[derive(Debug)]
 enum Direction {
     Up,
     Down,
     Left,
     Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+impl Direction {
+    fn offset(&self) -> SignedPoint {
+        match self {
+            Direction::Up => SignedPoint::new(0, -1),
+            Direction::Down => SignedPoint::new(0, 1),
+            Direction::Left => SignedPoint::new(-1, 0),
+            Direction::Right => SignedPoint::new(1, 0),
+            Direction::UpLeft => SignedPoint::new(-1, -1),
+            Direction::UpRight => SignedPoint::new(1, -1),
+            Direction::DownLeft => SignedPoint::new(-1, 1),
+            Direction::DownRight => SignedPoint::new(1, 1),
+        }
+    }
 }
 
 impl FromStr for Direction {
@@ -22,6 +52,10 @@ impl FromStr for Direction {
             "D" => Ok(Direction::Down),
             "L" => Ok(Direction::Left),
             "R" => Ok(Direction::Right),
+            "UL" => Ok(Direction::UpLeft),
+            "UR" => Ok(Direction::UpRight),
+            "DL" => Ok(Direction::DownLeft),
+            "DR" => Ok(Direction::DownRight),
             _ => Err(anyhow!("Unknown direction \"{}\"", value)),
         }
     }
@@ -33,132 +67,101 @@ struct Movement {
     magnitude: usize,
 }
 
-impl FromStr for Movement {
-    type Err = anyhow::Error;
+from_template!(Movement, "{direction} {magnitude}", {
+    direction,
+    magnitude,
+});
 
-    fn from_str(value: &str) -> Result<Self> {
-        let parts: Vec<&str> = value.split_whitespace().collect();
-        let direction: Direction = parts
-            .get(0)
-            .ok_or_else(|| anyhow!("Move \"{}\" missing direction", value))?
-            .parse()?;
-        let magnitude: usize = parts
-            .get(1)
-            .ok_or_else(|| anyhow!("Move \"{}\" missing magnitude", value))?
-            .parse()?;
-
-        Ok(Self {
-            direction,
-            magnitude,
-        })
-    }
+struct Rope {
+    knots: Vec<SignedPoint>,
+    /// Every position each knot has been in, indexed like `knots`.
+    visited: Vec<HashSet<SignedPoint>>,
 }
 
-#[derive(Debug)]
-struct Position {
-    x: i32,
-    y: i32,
-}
+impl Rope {
+    fn new(knot_count: usize) -> Result<Self> {
+        if knot_count == 0 {
+            return Err(anyhow!("A rope needs at least one knot"));
+        }
 
-impl Position {
-    fn zero() -> Self {
-        Position { x: 0, y: 0 }
+        let start = SignedPoint::default();
+        Ok(Rope {
+            knots: vec![start; knot_count],
+            visited: vec![vec![start].into_iter().collect(); knot_count],
+        })
     }
-}
 
-fn clamp(num: i32, min: i32, max: i32) -> i32 {
-    if num > max {
-        max
-    } else if num < min {
-        min
-    } else {
-        num
+    fn visited(&self, knot: usize) -> &HashSet<SignedPoint> {
+        &self.visited[knot]
     }
-}
 
-fn adjust_positions(position1: &mut Position, position2: &mut Position) {
-    let x_delta = position1.x - position2.x;
-    let y_delta = position1.y - position2.y;
-    if x_delta.abs() == 2 || y_delta.abs() == 2 {
-        position2.x += clamp(x_delta, -1, 1);
-        position2.y += clamp(y_delta, -1, 1);
+    fn tail_visited(&self) -> &HashSet<SignedPoint> {
+        self.visited(self.knots.len() - 1)
     }
-}
 
-struct Rope {
-    knots: Vec<Position>,
-}
+    /// Moves the head by `offset`, dragging each following knot a king move towards the
+    /// knot ahead of it whenever they stop touching.
+    fn step(&mut self, offset: SignedPoint) {
+        self.knots[0] += offset;
+        self.visited[0].insert(self.knots[0]);
 
-impl Rope {
-    fn new(knot_count: usize) -> Self {
-        let mut knots = Vec::new();
-        for _ in 0..knot_count {
-            knots.push(Position::zero());
-        }
+        for i in 1..self.knots.len() {
+            let delta = self.knots[i - 1] - self.knots[i];
+            if delta.chebyshev_length() <= 1 {
+                break;
+            }
 
-        Rope { knots }
+            self.knots[i] += delta.signum();
+            self.visited[i].insert(self.knots[i]);
+        }
     }
 
-    fn process_movement(&mut self, movement: &Movement, visited: &mut HashSet<(i32, i32)>) {
-        visited.insert((
-            self.knots[self.knots.len() - 1].x,
-            self.knots[self.knots.len() - 1].y,
-        ));
-
+    fn process_movement(&mut self, movement: &Movement) {
         for _ in 0..movement.magnitude {
-            match movement.direction {
-                Direction::Up => {
-                    self.knots[0].y -= 1;
-                }
-                Direction::Down => {
-                    self.knots[0].y += 1;
-                }
-                Direction::Left => {
-                    self.knots[0].x -= 1;
-                }
-                Direction::Right => {
-                    self.knots[0].x += 1;
-                }
-            }
-
-            for i in 0..self.knots.len() - 1 {
-                let mut first = self.knots.remove(i);
-                let mut second = self.knots.remove(i);
-                adjust_positions(&mut first, &mut second);
-                self.knots.insert(i, second);
-                self.knots.insert(i, first);
-            }
+            self.step(movement.direction.offset());
+        }
+    }
 
-            visited.insert((
-                self.knots[self.knots.len() - 1].x,
-                self.knots[self.knots.len() - 1].y,
-            ));
+    /// Draws the knots as in the puzzle text, over the cells the tail has visited.
+    fn render(&self) -> String {
+        let mut grid = SparseGrid::new('.');
+        for position in self.tail_visited().iter() {
+            grid.set(position.x, position.y, '#');
         }
+        grid.set(0, 0, 's');
+
+        for (i, knot) in self.knots.iter().enumerate().rev() {
+            let label = match i {
+                0 => 'H',
+                _ => std::char::from_digit(i as u32, 36).unwrap_or('*'),
+            };
+            grid.set(knot.x, knot.y, label);
+        }
+
+        grid.render(|c| *c).to_string()
     }
 }
 
-fn main() -> Result<()> {
-    let args = Args::from_args();
-
-    let mut rope = Rope::new(2);
-    let mut visited = HashSet::new();
+fn simulate(args: &Args, knot_count: usize) -> Result<usize> {
+    let mut rope = Rope::new(knot_count)?;
 
     for line in std::fs::read_to_string(&args.filename)?.lines() {
         let movement: Movement = line.parse()?;
-        rope.process_movement(&movement, &mut visited);
-    }
+        rope.process_movement(&movement);
 
-    println!("{}", visited.len());
+        if args.show {
+            println!("== {} ==\n{}", line, rope.render());
+        }
+    }
 
-    let mut rope = Rope::new(10);
-    let mut visited = HashSet::new();
+    Ok(rope.tail_visited().len())
+}
 
-    for line in std::fs::read_to_string(&args.filename)?.lines() {
-        let movement: Movement = line.parse()?;
-        rope.process_movement(&movement, &mut visited);
-    }
+fn main() -> Result<()> {
+    let args = Args::from_args();
 
-    println!("{}", visited.len());
+    println!("{}", simulate(&args, 2)?);
+    println!("{}", simulate(&args, 10)?);
 
     Ok(())
 }
@@ -0,0 +1,269 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+/// A directed graph with non-negative edge costs, explored lazily from its nodes.
+pub trait Graph {
+    type Node: Clone + Eq + Hash;
+
+    /// The nodes one edge away from `node`, with the cost of each edge.
+    fn neighbors(&self, node: &Self::Node) -> Vec<(Self::Node, u64)>;
+}
+
+/// Costs and shortest-path parents of every node settled by a search.
+#[derive(Debug)]
+pub struct Search<N> {
+    pub costs: HashMap<N, u64>,
+    parents: HashMap<N, N>,
+    /// The first target reached, if the search stopped at one.
+    pub target: Option<N>,
+}
+
+impl<N: Clone + Eq + Hash> Search<N> {
+    fn new() -> Self {
+        Search {
+            costs: HashMap::new(),
+            parents: HashMap::new(),
+            target: None,
+        }
+    }
+
+    pub fn cost(&self, node: &N) -> Option<u64> {
+        self.costs.get(node).copied()
+    }
+
+    /// The cost of reaching the target the search stopped at.
+    pub fn target_cost(&self) -> Option<u64> {
+        self.target.as_ref().and_then(|target| self.cost(target))
+    }
+
+    /// The nodes from the nearest source to `node`, inclusive of both.
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        if !self.costs.contains_key(node) {
+            return None;
+        }
+
+        let mut path = vec![node.clone()];
+        while let Some(parent) = self.parents.get(&path[path.len() - 1]) {
+            path.push(parent.clone());
+        }
+        path.reverse();
+
+        Some(path)
+    }
+}
+
+/// A heap entry ordered so that `BinaryHeap` pops the lowest priority first.
+struct Queued<N> {
+    priority: u64,
+    cost: u64,
+    node: N,
+}
+
+impl<N> PartialEq for Queued<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<N> Eq for Queued<N> {}
+
+impl<N> PartialOrd for Queued<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N> Ord for Queued<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+/// Breadth-first search treating every edge as cost 1. Stops at the first node matching
+/// `is_target`; pass `|_| false` to reach everything.
+pub fn bfs<G, I, F>(graph: &G, sources: I, is_target: F) -> Search<G::Node>
+where
+    G: Graph,
+    I: IntoIterator<Item = G::Node>,
+    F: Fn(&G::Node) -> bool,
+{
+    let mut search = Search::new();
+    let mut to_visit = VecDeque::new();
+
+    for source in sources {
+        if !search.costs.contains_key(&source) {
+            search.costs.insert(source.clone(), 0);
+            to_visit.push_back(source);
+        }
+    }
+
+    while let Some(node) = to_visit.pop_front() {
+        if is_target(&node) {
+            search.target = Some(node);
+            break;
+        }
+
+        let cost = search.costs[&node];
+        for (neighbor, _) in graph.neighbors(&node) {
+            if search.costs.contains_key(&neighbor) {
+                continue;
+            }
+
+            search.costs.insert(neighbor.clone(), cost + 1);
+            search.parents.insert(neighbor.clone(), node.clone());
+            to_visit.push_back(neighbor);
+        }
+    }
+
+    search
+}
+
+/// Best-first search ordered by cost plus `heuristic`. With a heuristic of zero this is
+/// Dijkstra's algorithm. Nodes are settled the first time they are popped, so any other
+/// heuristic must be consistent: h(n) <= cost(n, m) + h(m) for every edge, and zero at targets.
+fn best_first<G, I, F, H>(graph: &G, sources: I, is_target: F, heuristic: H) -> Search<G::Node>
+where
+    G: Graph,
+    I: IntoIterator<Item = G::Node>,
+    F: Fn(&G::Node) -> bool,
+    H: Fn(&G::Node) -> u64,
+{
+    let mut search = Search::new();
+    let mut best = HashMap::new();
+    let mut heap = BinaryHeap::new();
+
+    for source in sources {
+        best.insert(source.clone(), 0);
+        heap.push(Queued {
+            priority: heuristic(&source),
+            cost: 0,
+            node: source,
+        });
+    }
+
+    while let Some(Queued { cost, node, .. }) = heap.pop() {
+        if search.costs.contains_key(&node) {
+            continue;
+        }
+
+        search.costs.insert(node.clone(), cost);
+
+        if is_target(&node) {
+            search.target = Some(node);
+            break;
+        }
+
+        for (neighbor, edge) in graph.neighbors(&node) {
+            let next = cost + edge;
+            if search.costs.contains_key(&neighbor)
+                || best.get(&neighbor).is_some_and(|b| *b <= next)
+            {
+                continue;
+            }
+
+            best.insert(neighbor.clone(), next);
+            search.parents.insert(neighbor.clone(), node.clone());
+            heap.push(Queued {
+                priority: next + heuristic(&neighbor),
+                cost: next,
+                node: neighbor,
+            });
+        }
+    }
+
+    search
+}
+
+/// Dijkstra's algorithm from every source at once. Stops at the first node matching
+/// `is_target`; pass `|_| false` to settle everything reachable.
+pub fn dijkstra<G, I, F>(graph: &G, sources: I, is_target: F) -> Search<G::Node>
+where
+    G: Graph,
+    I: IntoIterator<Item = G::Node>,
+    F: Fn(&G::Node) -> bool,
+{
+    best_first(graph, sources, is_target, |_| 0)
+}
+
+/// A* search from `start` to the nearest node matching `is_target`, returning its cost and
+/// path. The heuristic must be consistent, not just admissible, or the path may be longer
+/// than the shortest.
+pub fn astar<G, F, H>(
+    graph: &G,
+    start: G::Node,
+    is_target: F,
+    heuristic: H,
+) -> Option<(u64, Vec<G::Node>)>
+where
+    G: Graph,
+    F: Fn(&G::Node) -> bool,
+    H: Fn(&G::Node) -> u64,
+{
+    let search = best_first(graph, vec![start], is_target, heuristic);
+    let target = search.target.as_ref()?;
+
+    Some((search.cost(target)?, search.path_to(target)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A weighted graph where the direct edge is dearer than the detour.
+    struct Triangle;
+
+    impl Graph for Triangle {
+        type Node = char;
+
+        fn neighbors(&self, node: &char) -> Vec<(char, u64)> {
+            match node {
+                'a' => vec![('b', 1), ('c', 5)],
+                'b' => vec![('c', 1)],
+                _ => Vec::new(),
+            }
+        }
+    }
+
+    /// An open grid with unit costs.
+    struct Open;
+
+    impl Graph for Open {
+        type Node = (i64, i64);
+
+        fn neighbors(&self, (x, y): &(i64, i64)) -> Vec<((i64, i64), u64)> {
+            vec![
+                ((x + 1, *y), 1),
+                ((x - 1, *y), 1),
+                ((*x, y + 1), 1),
+                ((*x, y - 1), 1),
+            ]
+        }
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let search = dijkstra(&Triangle, vec!['a'], |_| false);
+        assert_eq!(search.cost(&'c'), Some(2));
+        assert_eq!(search.path_to(&'c'), Some(vec!['a', 'b', 'c']));
+
+        let search = bfs(&Triangle, vec!['a'], |node| *node == 'c');
+        assert_eq!(search.target_cost(), Some(1));
+    }
+
+    #[test]
+    fn test_astar() {
+        let target = (3, -2);
+        let (cost, path) = astar(
+            &Open,
+            (0, 0),
+            |node| *node == target,
+            |(x, y)| ((x - target.0).abs() + (y - target.1).abs()) as u64,
+        )
+        .unwrap();
+
+        assert_eq!(cost, 5);
+        assert_eq!(path.len(), 6);
+        assert_eq!(path[5], target);
+    }
+}
@@ -0,0 +1,243 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+
+use crate::sparse_grid::SparseGrid;
+use crate::Matrix;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+
+    fn hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+/// A rectangular grid of tiles that can be drawn, indexed from the top left corner.
+pub trait Raster {
+    type Tile;
+
+    fn width(&self) -> usize;
+
+    fn height(&self) -> usize;
+
+    fn tile(&self, x: usize, y: usize) -> Self::Tile;
+}
+
+impl<T: Copy> Raster for Matrix<T> {
+    type Tile = T;
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn tile(&self, x: usize, y: usize) -> T {
+        self.data[y][x]
+    }
+}
+
+/// Draws the bounding box of the grid, so the top left pixel is its minimum corner.
+impl<T: Clone> Raster for SparseGrid<T> {
+    type Tile = T;
+
+    fn width(&self) -> usize {
+        self.bounds().map(|bounds| bounds.width()).unwrap_or(0)
+    }
+
+    fn height(&self) -> usize {
+        self.bounds().map(|bounds| bounds.height()).unwrap_or(0)
+    }
+
+    fn tile(&self, x: usize, y: usize) -> T {
+        match self.bounds() {
+            Some(bounds) => self
+                .get(bounds.min_x + x as i64, bounds.min_y + y as i64)
+                .clone(),
+            None => self.default_value().clone(),
+        }
+    }
+}
+
+/// Renders a grid to an image, colouring each tile with `palette` and drawing it as a
+/// `scale` by `scale` block of pixels.
+pub struct Image<'a, G, F> {
+    grid: &'a G,
+    palette: F,
+    scale: usize,
+}
+
+impl<'a, G, F> Image<'a, G, F>
+where
+    G: Raster,
+    F: Fn(&G::Tile) -> Rgb,
+{
+    pub fn new(grid: &'a G, palette: F) -> Self {
+        Image {
+            grid,
+            palette,
+            scale: 1,
+        }
+    }
+
+    pub fn scale(mut self, scale: usize) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    fn pixel_width(&self) -> usize {
+        self.grid.width() * self.scale
+    }
+
+    fn pixel_height(&self) -> usize {
+        self.grid.height() * self.scale
+    }
+
+    fn pixels(&self) -> Vec<u8> {
+        let mut pixels = Vec::with_capacity(self.pixel_width() * self.pixel_height() * 3);
+
+        for y in 0..self.grid.height() {
+            let mut row = Vec::with_capacity(self.pixel_width() * 3);
+            for x in 0..self.grid.width() {
+                let color = (self.palette)(&self.grid.tile(x, y));
+                for _ in 0..self.scale {
+                    row.extend_from_slice(&[color.0, color.1, color.2]);
+                }
+            }
+
+            for _ in 0..self.scale {
+                pixels.extend_from_slice(&row);
+            }
+        }
+
+        pixels
+    }
+
+    pub fn write_ppm<W: Write>(&self, mut writer: W) -> Result<()> {
+        write!(
+            writer,
+            "P6\n{} {}\n255\n",
+            self.pixel_width(),
+            self.pixel_height()
+        )?;
+        writer.write_all(&self.pixels())?;
+
+        Ok(())
+    }
+
+    pub fn write_png<W: Write>(&self, writer: W) -> Result<()> {
+        let mut encoder = png::Encoder::new(
+            writer,
+            self.pixel_width() as u32,
+            self.pixel_height() as u32,
+        );
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels())?;
+        writer.finish()?;
+
+        Ok(())
+    }
+
+    pub fn write_svg<W: Write>(&self, mut writer: W) -> Result<()> {
+        writeln!(
+            writer,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" shape-rendering=\"crispEdges\">",
+            self.pixel_width(),
+            self.pixel_height()
+        )?;
+
+        for y in 0..self.grid.height() {
+            for x in 0..self.grid.width() {
+                writeln!(
+                    writer,
+                    "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                    x * self.scale,
+                    y * self.scale,
+                    self.scale,
+                    self.scale,
+                    (self.palette)(&self.grid.tile(x, y)).hex()
+                )?;
+            }
+        }
+
+        writeln!(writer, "</svg>")?;
+
+        Ok(())
+    }
+
+    /// Writes the image to `path`, picking the format from its extension.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let extension = path.extension().and_then(|extension| extension.to_str());
+        if !matches!(extension, Some("ppm" | "png" | "svg")) {
+            return Err(anyhow!(
+                "Unknown image format for {} (expected .ppm, .png or .svg)",
+                path.display()
+            ));
+        }
+
+        // Flush explicitly, as dropping the writer would ignore a failed final write.
+        let mut writer = BufWriter::new(File::create(path)?);
+        match extension {
+            Some("ppm") => self.write_ppm(&mut writer)?,
+            Some("png") => self.write_png(&mut writer)?,
+            _ => self.write_svg(&mut writer)?,
+        }
+        writer.flush()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scaled_ppm() -> Result<()> {
+        let matrix = Matrix {
+            data: vec![vec![true, false]],
+            width: 2,
+            height: 1,
+        };
+
+        let mut output = Vec::new();
+        Image::new(&matrix, |on| if *on { Rgb::WHITE } else { Rgb::BLACK })
+            .scale(2)
+            .write_ppm(&mut output)?;
+
+        let mut expected = b"P6\n4 2\n255\n".to_vec();
+        for _ in 0..2 {
+            expected.extend_from_slice(&[255, 255, 255, 255, 255, 255, 0, 0, 0, 0, 0, 0]);
+        }
+        assert_eq!(output, expected);
+
+        Ok(())
+    }
+    #[test]
+    fn test_png_is_finished() -> Result<()> {
+        let matrix = Matrix {
+            data: vec![vec![true]],
+            width: 1,
+            height: 1,
+        };
+
+        let mut output = Vec::new();
+        Image::new(&matrix, |_| Rgb::WHITE).write_png(&mut output)?;
+        assert_eq!(&output[output.len() - 8..output.len() - 4], b"IEND");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,166 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::template::TemplateError;
+
+/// Text from an input file that failed to parse, with where it came from. Displays the
+/// offending text with a caret under the problem.
+#[derive(Debug)]
+pub struct ParseError {
+    pub filename: String,
+    /// The 1-based line the problem is on, or the text starts on if the underlying error
+    /// doesn't say.
+    pub line_number: usize,
+    pub text: String,
+    /// The 1-based column on that line the problem starts at, counted in characters, if the
+    /// underlying error says.
+    pub column: Option<usize>,
+    /// Which line of `text` the problem is on.
+    line_index: usize,
+    source: Box<dyn Error + Send + Sync>,
+}
+
+impl ParseError {
+    pub fn new<E>(filename: &str, line_number: usize, text: &str, source: E) -> Self
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        let source = source.into();
+        let column = source
+            .downcast_ref::<TemplateError>()
+            .and_then(TemplateError::column);
+
+        ParseError::with_column(filename, line_number, text, column, source)
+    }
+
+    /// Like `new`, for an error already converted to `anyhow::Error`, which would otherwise
+    /// hide where the underlying error says the problem starts.
+    pub fn from_anyhow(
+        filename: &str,
+        line_number: usize,
+        text: &str,
+        source: anyhow::Error,
+    ) -> Self {
+        let column = source
+            .downcast_ref::<TemplateError>()
+            .and_then(TemplateError::column);
+
+        ParseError::with_column(filename, line_number, text, column, source.into())
+    }
+
+    /// `column` counts characters from the start of `text`, which may span several lines.
+    fn with_column(
+        filename: &str,
+        line_number: usize,
+        text: &str,
+        column: Option<usize>,
+        source: Box<dyn Error + Send + Sync>,
+    ) -> Self {
+        let mut line_index = 0;
+        let column = column.map(|column| {
+            let mut line_start = 0;
+            for (index, c) in text.chars().take(column - 1).enumerate() {
+                if c == '\n' {
+                    line_index += 1;
+                    line_start = index + 1;
+                }
+            }
+
+            column - line_start
+        });
+
+        ParseError {
+            filename: filename.to_string(),
+            line_number: line_number + line_index,
+            text: text.to_string(),
+            column,
+            line_index,
+            source,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.column {
+            Some(column) => write!(
+                f,
+                "{}:{}:{}: {}",
+                self.filename, self.line_number, column, self.source
+            )?,
+            None => write!(f, "{}:{}: {}", self.filename, self.line_number, self.source)?,
+        }
+
+        for (index, line) in self.text.lines().enumerate() {
+            write!(f, "\n    {}", line)?;
+
+            match self.column {
+                Some(column) if index == self.line_index => {
+                    write!(f, "\n    {}^", " ".repeat(column - 1))?
+                }
+                None if self.text.lines().count() == 1 => {
+                    write!(f, "\n    {}", "^".repeat(line.chars().count()))?
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for ParseError {
+    /// The underlying error's message is already part of this one, so skip to its cause.
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.source()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::template::Template;
+
+    #[test]
+    fn test_display() {
+        let err = "x".parse::<usize>().unwrap_err();
+        assert_eq!(
+            ParseError::new("input.txt", 3, "12x", err).to_string(),
+            "input.txt:3: invalid digit found in string\n    12x\n    ^^^"
+        );
+
+        let err = Template::new("{low}-{high}")
+            .unwrap()
+            .captures("1-x")
+            .and_then(|captures| captures.parse::<usize>("high"))
+            .unwrap_err();
+        let err = ParseError::new("input.txt", 7, "1-x", err);
+        assert_eq!(err.column, Some(3));
+        assert!(err.to_string().ends_with("\n    1-x\n      ^"));
+    }
+
+    #[test]
+    fn test_column() {
+        let template = Template::new("{name}: {count}").unwrap();
+
+        let err = template
+            .captures("café: x")
+            .and_then(|captures| captures.parse::<usize>("count"))
+            .unwrap_err();
+        let err = ParseError::new("input.txt", 2, "café: x", err);
+        assert_eq!((err.line_number, err.column), (2, Some(7)));
+        assert!(err.to_string().ends_with("\n    café: x\n          ^"));
+
+        let text = "a: 1\nbé: x\nc: 3";
+        let err = Template::new("a: {a}\nbé: {b}\nc: {c}")
+            .unwrap()
+            .captures(text)
+            .and_then(|captures| captures.parse::<usize>("b"))
+            .unwrap_err();
+        let err = ParseError::new("input.txt", 4, text, err);
+        assert_eq!((err.line_number, err.column), (5, Some(5)));
+        assert!(err
+            .to_string()
+            .ends_with("\n    a: 1\n    bé: x\n        ^\n    c: 3"));
+    }
+}
@@ -0,0 +1,197 @@
+use crate::neighbors::Topology;
+use crate::{CoordinateValue, Matrix};
+
+/// A compass direction, with north pointing towards row 0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    pub fn cardinal() -> [Direction; 4] {
+        [
+            Direction::North,
+            Direction::East,
+            Direction::South,
+            Direction::West,
+        ]
+    }
+
+    pub fn all() -> [Direction; 8] {
+        [
+            Direction::North,
+            Direction::NorthEast,
+            Direction::East,
+            Direction::SouthEast,
+            Direction::South,
+            Direction::SouthWest,
+            Direction::West,
+            Direction::NorthWest,
+        ]
+    }
+
+    pub fn offset(&self) -> (i64, i64) {
+        match self {
+            Direction::North => (0, -1),
+            Direction::NorthEast => (1, -1),
+            Direction::East => (1, 0),
+            Direction::SouthEast => (1, 1),
+            Direction::South => (0, 1),
+            Direction::SouthWest => (-1, 1),
+            Direction::West => (-1, 0),
+            Direction::NorthWest => (-1, -1),
+        }
+    }
+}
+
+/// The cells met walking from a starting cell to the edge of a matrix, excluding the start.
+pub struct Ray<'a, T: Copy> {
+    matrix: &'a Matrix<T>,
+    x: i64,
+    y: i64,
+    direction: (i64, i64),
+}
+
+impl<'a, T: Copy> Iterator for Ray<'a, T> {
+    type Item = CoordinateValue<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.x += self.direction.0;
+        self.y += self.direction.1;
+
+        self.matrix
+            .get_with_topology(self.x, self.y, Topology::Bounded)
+    }
+}
+
+/// A ray that also stops after yielding the first cell matching its predicate.
+pub struct RayUntil<'a, T: Copy, P> {
+    ray: Ray<'a, T>,
+    stop: P,
+    done: bool,
+}
+
+impl<'a, T, P> Iterator for RayUntil<'a, T, P>
+where
+    T: Copy,
+    P: Fn(&CoordinateValue<T>) -> bool,
+{
+    type Item = CoordinateValue<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let cell = self.ray.next()?;
+        self.done = (self.stop)(&cell);
+
+        Some(cell)
+    }
+}
+
+impl<T: Copy> Matrix<T> {
+    pub fn ray(&self, x: usize, y: usize, direction: Direction) -> Ray<'_, T> {
+        Ray {
+            matrix: self,
+            x: x as i64,
+            y: y as i64,
+            direction: direction.offset(),
+        }
+    }
+
+    /// Like `ray`, but the cell matching `stop` is the last one yielded.
+    pub fn ray_until<P>(
+        &self,
+        x: usize,
+        y: usize,
+        direction: Direction,
+        stop: P,
+    ) -> RayUntil<'_, T, P>
+    where
+        P: Fn(&CoordinateValue<T>) -> bool,
+    {
+        RayUntil {
+            ray: self.ray(x, y, direction),
+            stop,
+            done: false,
+        }
+    }
+
+    pub fn row(&self, y: usize) -> impl Iterator<Item = T> + '_ {
+        self.data
+            .get(y)
+            .into_iter()
+            .flat_map(|row| row.iter().copied())
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = T> + '_ {
+        self.data.iter().filter_map(move |row| row.get(x).copied())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Point;
+
+    fn matrix() -> Matrix<u32> {
+        Matrix {
+            data: vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]],
+            width: 3,
+            height: 3,
+        }
+    }
+
+    fn values<I: Iterator<Item = CoordinateValue<u32>>>(cells: I) -> Vec<u32> {
+        cells.map(|cell| cell.value).collect()
+    }
+
+    #[test]
+    fn test_ray() {
+        let matrix = matrix();
+
+        assert_eq!(values(matrix.ray(0, 1, Direction::East)), vec![5, 6]);
+        assert_eq!(values(matrix.ray(2, 2, Direction::NorthWest)), vec![5, 1]);
+
+        let cells: Vec<Point> = matrix
+            .ray(1, 2, Direction::North)
+            .map(|cell| cell.coordinates)
+            .collect();
+        assert_eq!(cells, vec![Point::new(1, 1), Point::new(1, 0)]);
+    }
+
+    #[test]
+    fn test_ray_stops_at_edge() {
+        let matrix = matrix();
+
+        assert!(values(matrix.ray(0, 0, Direction::West)).is_empty());
+        assert!(values(matrix.ray(2, 0, Direction::NorthEast)).is_empty());
+        assert_eq!(values(matrix.ray(1, 1, Direction::SouthWest)), vec![7]);
+    }
+
+    #[test]
+    fn test_ray_until() {
+        let matrix = matrix();
+
+        assert_eq!(
+            values(matrix.ray_until(0, 0, Direction::South, |cell| cell.value > 3)),
+            vec![4]
+        );
+        assert_eq!(
+            values(matrix.ray_until(0, 0, Direction::SouthEast, |cell| cell.value == 9)),
+            vec![5, 9]
+        );
+        assert_eq!(
+            values(matrix.ray_until(0, 2, Direction::East, |_| false)),
+            vec![8, 9]
+        );
+    }
+}
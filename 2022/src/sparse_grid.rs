@@ -0,0 +1,203 @@
+use std::cmp::{max, min};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Inclusive bounding box of every cell that has been set in a `SparseGrid`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bounds {
+    pub min_x: i64,
+    pub min_y: i64,
+    pub max_x: i64,
+    pub max_y: i64,
+}
+
+impl Bounds {
+    fn from_point(x: i64, y: i64) -> Self {
+        Bounds {
+            min_x: x,
+            min_y: y,
+            max_x: x,
+            max_y: y,
+        }
+    }
+
+    fn include(&mut self, x: i64, y: i64) {
+        self.min_x = min(self.min_x, x);
+        self.min_y = min(self.min_y, y);
+        self.max_x = max(self.max_x, x);
+        self.max_y = max(self.max_y, y);
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max_x - self.min_x + 1) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max_y - self.min_y + 1) as usize
+    }
+
+    pub fn contains(&self, x: i64, y: i64) -> bool {
+        x >= self.min_x && x <= self.max_x && y >= self.min_y && y <= self.max_y
+    }
+}
+
+/// An unbounded grid that only stores cells that have been explicitly set.
+///
+/// Reads of unset cells return the grid's default value. The bounding box grows as cells
+/// are set and is never shrunk, so removing a cell leaves the bounds untouched.
+#[derive(Clone, Debug)]
+pub struct SparseGrid<T> {
+    data: HashMap<(i64, i64), T>,
+    default: T,
+    bounds: Option<Bounds>,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new(default: T) -> Self {
+        SparseGrid {
+            data: HashMap::new(),
+            default,
+            bounds: None,
+        }
+    }
+
+    pub fn get(&self, x: i64, y: i64) -> &T {
+        self.data.get(&(x, y)).unwrap_or(&self.default)
+    }
+
+    pub fn contains(&self, x: i64, y: i64) -> bool {
+        self.data.contains_key(&(x, y))
+    }
+
+    pub fn set(&mut self, x: i64, y: i64, value: T) -> Option<T> {
+        match self.bounds.as_mut() {
+            Some(bounds) => bounds.include(x, y),
+            None => self.bounds = Some(Bounds::from_point(x, y)),
+        }
+
+        self.data.insert((x, y), value)
+    }
+
+    pub fn remove(&mut self, x: i64, y: i64) -> Option<T> {
+        self.data.remove(&(x, y))
+    }
+
+    pub fn default_value(&self) -> &T {
+        &self.default
+    }
+
+    pub fn bounds(&self) -> Option<Bounds> {
+        self.bounds
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Iterates over explicitly set cells in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = ((i64, i64), &T)> {
+        self.data.iter().map(|(point, value)| (*point, value))
+    }
+
+    /// Iterates over row `y` from the left to the right edge of the bounding box, filling
+    /// unset cells with the default value.
+    pub fn row(&self, y: i64) -> impl Iterator<Item = (i64, &T)> {
+        let (min_x, max_x) = match self.bounds {
+            Some(bounds) => (bounds.min_x, bounds.max_x),
+            None => (0, -1),
+        };
+
+        (min_x..=max_x).map(move |x| (x, self.get(x, y)))
+    }
+
+    /// Iterates over column `x` from the top to the bottom edge of the bounding box, filling
+    /// unset cells with the default value.
+    pub fn column(&self, x: i64) -> impl Iterator<Item = (i64, &T)> {
+        let (min_y, max_y) = match self.bounds {
+            Some(bounds) => (bounds.min_y, bounds.max_y),
+            None => (0, -1),
+        };
+
+        (min_y..=max_y).map(move |y| (y, self.get(x, y)))
+    }
+
+    /// Renders the bounding box one line per row, mapping each tile to a character.
+    pub fn render<F>(&self, tile_char: F) -> Render<'_, T, F>
+    where
+        F: Fn(&T) -> char,
+    {
+        Render {
+            grid: self,
+            tile_char,
+        }
+    }
+}
+
+pub struct Render<'a, T, F> {
+    grid: &'a SparseGrid<T>,
+    tile_char: F,
+}
+
+impl<'a, T, F> fmt::Display for Render<'a, T, F>
+where
+    F: Fn(&T) -> char,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let bounds = match self.grid.bounds {
+            Some(bounds) => bounds,
+            None => return Ok(()),
+        };
+
+        for y in bounds.min_y..=bounds.max_y {
+            for (_, value) in self.grid.row(y) {
+                write!(f, "{}", (self.tile_char)(value))?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounds_grow_with_set() {
+        let mut grid = SparseGrid::new('.');
+        assert_eq!(grid.bounds(), None);
+
+        grid.set(2, -1, '#');
+        grid.set(-3, 4, '#');
+        grid.remove(-3, 4);
+
+        assert_eq!(
+            grid.bounds(),
+            Some(Bounds {
+                min_x: -3,
+                min_y: -1,
+                max_x: 2,
+                max_y: 4,
+            })
+        );
+        assert_eq!(*grid.get(-3, 4), '.');
+        assert_eq!(grid.len(), 1);
+    }
+
+    #[test]
+    fn test_render() {
+        let mut grid = SparseGrid::new(false);
+        grid.set(0, 0, true);
+        grid.set(2, 1, true);
+
+        let rendered = grid
+            .render(|value| if *value { '#' } else { '.' })
+            .to_string();
+        assert_eq!(rendered, "#..\n..#\n");
+    }
+}
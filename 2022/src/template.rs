@@ -0,0 +1,261 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum TemplateError {
+    #[error("Bad template \"{template}\": {reason}")]
+    BadTemplate { template: String, reason: String },
+
+    #[error(
+        "\"{line}\" does not match \"{template}\": expected \"{expected}\" at column {column}"
+    )]
+    Mismatch {
+        line: String,
+        template: String,
+        expected: String,
+        column: usize,
+    },
+
+    #[error("\"{line}\" has no field {field}")]
+    UnknownField { line: String, field: String },
+
+    #[error("Can't parse {field} from \"{value}\" in \"{line}\": {reason}")]
+    BadField {
+        line: String,
+        field: String,
+        value: String,
+        column: usize,
+        reason: String,
+    },
+}
+
+impl TemplateError {
+    /// The 1-based column, in characters, of the line where the problem starts, if it is tied to
+    /// one.
+    pub fn column(&self) -> Option<usize> {
+        match self {
+            TemplateError::Mismatch { column, .. } | TemplateError::BadField { column, .. } => {
+                Some(*column)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Part {
+    Literal(String),
+    Field(String),
+}
+
+/// A line format such as "move {quantity} from {source} to {destination}": literal text with
+/// named fields in braces. Each field takes everything up to the literal text after it.
+#[derive(Clone, Debug)]
+pub struct Template {
+    template: String,
+    parts: Vec<Part>,
+}
+
+impl Template {
+    pub fn new(template: &str) -> Result<Self, TemplateError> {
+        let bad = |reason: &str| TemplateError::BadTemplate {
+            template: template.to_string(),
+            reason: reason.to_string(),
+        };
+
+        let mut parts = Vec::new();
+        let mut rest = template;
+        while !rest.is_empty() {
+            match rest.find('{') {
+                Some(0) => {
+                    let end = rest.find('}').ok_or_else(|| bad("unclosed {"))?;
+                    let name = &rest[1..end];
+                    if name.is_empty() || name.contains('{') {
+                        return Err(bad("fields need a name"));
+                    }
+                    if let Some(Part::Field(_)) = parts.last() {
+                        return Err(bad("two fields need literal text between them"));
+                    }
+
+                    parts.push(Part::Field(name.to_string()));
+                    rest = &rest[end + 1..];
+                }
+                Some(start) => {
+                    parts.push(Part::Literal(rest[..start].to_string()));
+                    rest = &rest[start..];
+                }
+                None => {
+                    parts.push(Part::Literal(rest.to_string()));
+                    rest = "";
+                }
+            }
+        }
+
+        Ok(Template {
+            template: template.to_string(),
+            parts,
+        })
+    }
+
+    /// Matches `line` against the template, capturing the text of each field.
+    pub fn captures<'a>(&self, line: &'a str) -> Result<Captures<'a>, TemplateError> {
+        let mismatch = |expected: &str, offset: usize| TemplateError::Mismatch {
+            line: line.to_string(),
+            template: self.template.clone(),
+            expected: expected.to_string(),
+            column: line[..offset].chars().count() + 1,
+        };
+
+        let mut fields = Vec::new();
+        let mut position = 0;
+        for (index, part) in self.parts.iter().enumerate() {
+            match part {
+                Part::Literal(literal) => {
+                    if !line[position..].starts_with(literal.as_str()) {
+                        return Err(mismatch(literal, position));
+                    }
+                    position += literal.len();
+                }
+                Part::Field(name) => {
+                    let end = match self.parts.get(index + 1) {
+                        Some(Part::Literal(literal)) => {
+                            position
+                                + line[position..]
+                                    .find(literal.as_str())
+                                    .ok_or_else(|| mismatch(literal, position))?
+                        }
+                        _ => line.len(),
+                    };
+
+                    fields.push((name.clone(), position, &line[position..end]));
+                    position = end;
+                }
+            }
+        }
+
+        if position < line.len() {
+            return Err(mismatch("end of line", position));
+        }
+
+        Ok(Captures { line, fields })
+    }
+}
+
+/// The text captured for each field of a template.
+#[derive(Debug)]
+pub struct Captures<'a> {
+    line: &'a str,
+    /// Each field's name, the byte offset it starts at and its text.
+    fields: Vec<(String, usize, &'a str)>,
+}
+
+impl<'a> Captures<'a> {
+    fn find(&self, field: &str) -> Result<(usize, &'a str), TemplateError> {
+        self.fields
+            .iter()
+            .find(|(name, _, _)| name == field)
+            .map(|(_, offset, value)| (*offset, *value))
+            .ok_or_else(|| TemplateError::UnknownField {
+                line: self.line.to_string(),
+                field: field.to_string(),
+            })
+    }
+
+    pub fn get(&self, field: &str) -> Result<&'a str, TemplateError> {
+        Ok(self.find(field)?.1)
+    }
+
+    /// Parses a field, ignoring whitespace around it.
+    pub fn parse<T>(&self, field: &str) -> Result<T, TemplateError>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: Display,
+    {
+        let (offset, value) = self.find(field)?;
+        value
+            .trim()
+            .parse()
+            .map_err(|err: <T as FromStr>::Err| TemplateError::BadField {
+                line: self.line.to_string(),
+                field: field.to_string(),
+                value: value.to_string(),
+                column: self.line[..offset].chars().count() + 1,
+                reason: err.to_string(),
+            })
+    }
+}
+
+/// Implements `FromStr` for a struct by matching a template whose fields are named after the
+/// struct's fields, each of which is parsed with its own `FromStr`.
+#[macro_export]
+macro_rules! from_template {
+    ($type:ty, $template:expr, { $($field:ident),+ $(,)? }) => {
+        impl std::str::FromStr for $type {
+            type Err = $crate::template::TemplateError;
+
+            fn from_str(value: &str) -> Result<Self, Self::Err> {
+                let captures = $crate::template::Template::new($template)?.captures(value)?;
+
+                Ok(Self {
+                    $($field: captures.parse(stringify!($field))?,)+
+                })
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Movement {
+        quantity: usize,
+        source: usize,
+        destination: char,
+    }
+
+    from_template!(Movement, "move {quantity} from {source} to {destination}", {
+        quantity,
+        source,
+        destination,
+    });
+
+    #[test]
+    fn test_from_template() {
+        assert_eq!(
+            "move 12 from 3 to x".parse::<Movement>().unwrap(),
+            Movement {
+                quantity: 12,
+                source: 3,
+                destination: 'x',
+            }
+        );
+
+        let err = "move 12 from three to x".parse::<Movement>().unwrap_err();
+        assert!(matches!(err, TemplateError::BadField { ref field, .. } if field == "source"));
+        assert_eq!(err.column(), Some(14));
+
+        let err = "take 12 from 3 to x".parse::<Movement>().unwrap_err();
+        assert!(matches!(err, TemplateError::Mismatch { column: 1, .. }));
+
+        let err = "move 1 from 2 to x!".parse::<Movement>().unwrap_err();
+        assert!(matches!(err, TemplateError::BadField { ref field, .. } if field == "destination"));
+    }
+
+    #[test]
+    fn test_captures() {
+        let template = Template::new("{low}-{high} {letter}: {password}").unwrap();
+        let captures = template.captures("1-3 a: abcde").unwrap();
+
+        assert_eq!(captures.parse::<usize>("high").unwrap(), 3);
+        assert_eq!(captures.get("password").unwrap(), "abcde");
+        assert!(captures.get("missing").is_err());
+
+        assert!(Template::new("{a}{b}").is_err());
+        assert!(Template::new("{a").is_err());
+        assert!(template.captures("1-3 a abcde").is_err());
+    }
+}
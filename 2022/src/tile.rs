@@ -0,0 +1,179 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use anyhow::{anyhow, Result};
+
+use crate::{Matrix, Point};
+
+/// A grid cell that can be read from and written back to a single character.
+///
+/// Implementations should round-trip: `T::from_char(t.to_char())` gives back `t`.
+pub trait Tile: Sized {
+    fn from_char(c: char) -> Result<Self>;
+
+    fn to_char(&self) -> char;
+}
+
+/// Declares a fieldless enum along with its `Tile` implementation.
+///
+/// ```ignore
+/// tile_enum! {
+///     enum Space {
+///         Empty = '.',
+///         Tree = '#',
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! tile_enum {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($variant:ident = $c:literal),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        $vis enum $name {
+            $($variant),+
+        }
+
+        impl $crate::tile::Tile for $name {
+            fn from_char(c: char) -> ::anyhow::Result<Self> {
+                match c {
+                    $($c => Ok($name::$variant),)+
+                    _ => Err(::anyhow::anyhow!(
+                        "Invalid {} tile '{}'",
+                        stringify!($name),
+                        c
+                    )),
+                }
+            }
+
+            fn to_char(&self) -> char {
+                match self {
+                    $($name::$variant => $c,)+
+                }
+            }
+        }
+    };
+}
+
+/// A parsed char grid along with the locations of any marker characters found in it. Markers
+/// are stored in the grid as the tile they stand for, so they don't need a `Tile` of their own.
+#[derive(Debug)]
+pub struct MarkedMatrix<T: Copy> {
+    pub matrix: Matrix<T>,
+    pub markers: HashMap<char, Vec<Point>>,
+}
+
+impl<T: Copy> MarkedMatrix<T> {
+    /// Returns the position of a marker that must appear exactly once.
+    pub fn marker(&self, c: char) -> Result<Point> {
+        match self.markers.get(&c).map(|points| points.as_slice()) {
+            Some([point]) => Ok(point.clone()),
+            Some(points) if !points.is_empty() => Err(anyhow!(
+                "Found marker '{}' {} times (first at {}, second at {})",
+                c,
+                points.len(),
+                points[0],
+                points[1]
+            )),
+            _ => Err(anyhow!("No marker '{}' found", c)),
+        }
+    }
+}
+
+/// Parses a char grid. `markers` pairs each marker character with the tile character it
+/// stands for, e.g. ('S', 'a') for a start square at the lowest elevation.
+pub fn parse_tile_matrix<T>(contents: &str, markers: &[(char, char)]) -> Result<MarkedMatrix<T>>
+where
+    T: Tile + Copy,
+{
+    let markers: HashMap<char, char> = markers.iter().copied().collect();
+
+    let mut data: Vec<Vec<T>> = Vec::new();
+    let mut found: HashMap<char, Vec<Point>> = HashMap::new();
+    let mut widths = HashSet::new();
+
+    for (i, line) in contents.lines().enumerate() {
+        let mut row: Vec<T> = Vec::new();
+        for (j, c) in line.chars().enumerate() {
+            let tile = match markers.get(&c) {
+                Some(tile) => {
+                    found.entry(c).or_default().push(Point::new(j, i));
+                    *tile
+                }
+                None => c,
+            };
+
+            row.push(T::from_char(tile)?);
+        }
+        widths.insert(row.len());
+        data.push(row);
+    }
+
+    if widths.len() > 1 {
+        return Err(anyhow!("Multiple row widths detected"));
+    }
+
+    let width = widths.into_iter().next().unwrap_or(0);
+    let height = data.len();
+
+    Ok(MarkedMatrix {
+        matrix: Matrix {
+            data,
+            width,
+            height,
+        },
+        markers: found,
+    })
+}
+
+pub fn read_tile_matrix<T>(filename: &str, markers: &[(char, char)]) -> Result<MarkedMatrix<T>>
+where
+    T: Tile + Copy,
+{
+    parse_tile_matrix(&std::fs::read_to_string(filename)?, markers)
+}
+
+impl<T: Tile + Copy> fmt::Display for Matrix<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.data.iter() {
+            for value in row.iter() {
+                write!(f, "{}", value.to_char())?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    tile_enum! {
+        enum Space {
+            Open = '.',
+            Wall = '#',
+        }
+    }
+
+    #[test]
+    fn test_round_trip_with_markers() -> Result<()> {
+        let contents = "S.#\n.#.\n";
+
+        let parsed: MarkedMatrix<Space> = parse_tile_matrix(contents, &[('S', '.'), ('E', '.')])?;
+
+        assert_eq!(parsed.matrix.get(0, 0), Some(Space::Open));
+        assert_eq!(parsed.matrix.get(2, 0), Some(Space::Wall));
+        assert_eq!(parsed.marker('S')?, Point::new(0, 0));
+        assert!(parsed.marker('E').is_err());
+        assert_eq!(parsed.matrix.to_string(), "..#\n.#.\n");
+        assert!(Space::from_char('S').is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,304 @@
+use anyhow::{anyhow, Result};
+
+use crate::Matrix;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rotation {
+    Clockwise90,
+    Clockwise180,
+    Clockwise270,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Edge {
+    Top,
+    Right,
+    Bottom,
+    Left,
+}
+
+fn from_rows<T: Copy>(data: Vec<Vec<T>>) -> Matrix<T> {
+    let height = data.len();
+    let width = data.first().map(|row| row.len()).unwrap_or(0);
+
+    Matrix {
+        data,
+        width,
+        height,
+    }
+}
+
+impl<T: Copy> Matrix<T> {
+    fn map_coordinates<F>(&self, width: usize, height: usize, source: F) -> Matrix<T>
+    where
+        F: Fn(usize, usize) -> (usize, usize),
+    {
+        from_rows(
+            (0..height)
+                .map(|y| {
+                    (0..width)
+                        .map(|x| {
+                            let (source_x, source_y) = source(x, y);
+                            self.data[source_y][source_x]
+                        })
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+
+    pub fn transpose(&self) -> Matrix<T> {
+        self.map_coordinates(self.height, self.width, |x, y| (y, x))
+    }
+
+    pub fn rotate(&self, rotation: Rotation) -> Matrix<T> {
+        let (width, height) = (self.width, self.height);
+        match rotation {
+            Rotation::Clockwise90 => {
+                self.map_coordinates(height, width, |x, y| (y, height - 1 - x))
+            }
+            Rotation::Clockwise180 => {
+                self.map_coordinates(width, height, |x, y| (width - 1 - x, height - 1 - y))
+            }
+            Rotation::Clockwise270 => {
+                self.map_coordinates(height, width, |x, y| (width - 1 - y, x))
+            }
+        }
+    }
+
+    /// Mirrors left to right.
+    pub fn flip_horizontal(&self) -> Matrix<T> {
+        let width = self.width;
+        self.map_coordinates(self.width, self.height, |x, y| (width - 1 - x, y))
+    }
+
+    /// Mirrors top to bottom.
+    pub fn flip_vertical(&self) -> Matrix<T> {
+        let height = self.height;
+        self.map_coordinates(self.width, self.height, |x, y| (x, height - 1 - y))
+    }
+
+    /// All eight rotations and reflections, starting with the matrix itself.
+    pub fn orientations(&self) -> impl Iterator<Item = Matrix<T>> + '_ {
+        let flipped = self.flip_horizontal();
+
+        [self.clone(), flipped].into_iter().flat_map(|matrix| {
+            [
+                None,
+                Some(Rotation::Clockwise90),
+                Some(Rotation::Clockwise180),
+                Some(Rotation::Clockwise270),
+            ]
+            .into_iter()
+            .map(move |rotation| match rotation {
+                Some(rotation) => matrix.rotate(rotation),
+                None => matrix.clone(),
+            })
+        })
+    }
+
+    pub fn crop(&self, x: usize, y: usize, width: usize, height: usize) -> Result<Matrix<T>> {
+        if x + width > self.width || y + height > self.height {
+            return Err(anyhow!(
+                "Crop of {}x{} at ({}, {}) doesn't fit in {}x{} matrix",
+                width,
+                height,
+                x,
+                y,
+                self.width,
+                self.height
+            ));
+        }
+
+        Ok(self.map_coordinates(width, height, |j, i| (x + j, y + i)))
+    }
+
+    /// Returns the cells along an edge, reading left to right or top to bottom.
+    pub fn edge(&self, edge: Edge) -> Vec<T> {
+        match edge {
+            Edge::Top => self.data.first().cloned().unwrap_or_default(),
+            Edge::Bottom => self.data.last().cloned().unwrap_or_default(),
+            Edge::Left => self
+                .data
+                .iter()
+                .filter_map(|row| row.first())
+                .copied()
+                .collect(),
+            Edge::Right => self
+                .data
+                .iter()
+                .filter_map(|row| row.last())
+                .copied()
+                .collect(),
+        }
+    }
+
+    /// Joins a grid of matrices into one. Every row must hold the same number of matrices,
+    /// every matrix in a row must share a height and every matrix in a column must share a
+    /// width.
+    pub fn stitch(tiles: &[Vec<Matrix<T>>]) -> Result<Matrix<T>> {
+        let mut data = Vec::new();
+        let columns = tiles.first().map(|tile_row| tile_row.len()).unwrap_or(0);
+
+        for (i, tile_row) in tiles.iter().enumerate() {
+            if tile_row.len() != columns {
+                return Err(anyhow!(
+                    "Tile row {} has {} tiles, expected {}",
+                    i,
+                    tile_row.len(),
+                    columns
+                ));
+            }
+
+            let height = tile_row.first().map(|tile| tile.height).unwrap_or(0);
+
+            for (j, tile) in tile_row.iter().enumerate() {
+                if tile.data.len() != tile.height
+                    || tile.data.iter().any(|row| row.len() != tile.width)
+                {
+                    return Err(anyhow!(
+                        "Tile ({}, {}) doesn't match its own {}x{} size",
+                        j,
+                        i,
+                        tile.width,
+                        tile.height
+                    ));
+                }
+
+                if tile.height != height {
+                    return Err(anyhow!(
+                        "Tile ({}, {}) has height {}, expected {}",
+                        j,
+                        i,
+                        tile.height,
+                        height
+                    ));
+                }
+
+                if let Some(above) = i.checked_sub(1).and_then(|row| tiles[row].get(j)) {
+                    if above.width != tile.width {
+                        return Err(anyhow!(
+                            "Tile ({}, {}) has width {}, expected {}",
+                            j,
+                            i,
+                            tile.width,
+                            above.width
+                        ));
+                    }
+                }
+            }
+
+            for y in 0..height {
+                data.push(
+                    tile_row
+                        .iter()
+                        .flat_map(|tile| tile.data[y].iter().copied())
+                        .collect::<Vec<T>>(),
+                );
+            }
+        }
+
+        Ok(from_rows(data))
+    }
+
+    /// Repeats the matrix `across` times horizontally and `down` times vertically.
+    pub fn tile(&self, across: usize, down: usize) -> Matrix<T> {
+        let data = (0..down * self.height)
+            .map(|y| {
+                self.data[y % self.height]
+                    .iter()
+                    .copied()
+                    .cycle()
+                    .take(across * self.width)
+                    .collect()
+            })
+            .collect();
+
+        from_rows(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix() -> Matrix<u32> {
+        from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]])
+    }
+
+    #[test]
+    fn test_rotate() {
+        let matrix = matrix();
+
+        assert_eq!(
+            matrix.rotate(Rotation::Clockwise90).data,
+            vec![vec![4, 1], vec![5, 2], vec![6, 3]]
+        );
+        assert_eq!(
+            matrix.rotate(Rotation::Clockwise180).data,
+            vec![vec![6, 5, 4], vec![3, 2, 1]]
+        );
+        assert_eq!(
+            matrix.rotate(Rotation::Clockwise270).data,
+            vec![vec![3, 6], vec![2, 5], vec![1, 4]]
+        );
+    }
+
+    #[test]
+    fn test_orientations_are_distinct() {
+        let matrix = matrix();
+
+        let mut orientations: Vec<Vec<Vec<u32>>> =
+            matrix.orientations().map(|matrix| matrix.data).collect();
+        orientations.sort();
+        orientations.dedup();
+
+        assert_eq!(orientations.len(), 8);
+    }
+
+    #[test]
+    fn test_stitch_and_crop() -> Result<()> {
+        let matrix = matrix();
+
+        let stitched = Matrix::stitch(&[
+            vec![matrix.clone(), matrix.flip_horizontal()],
+            vec![
+                matrix.flip_vertical(),
+                matrix.rotate(Rotation::Clockwise180),
+            ],
+        ])?;
+
+        assert_eq!(stitched.width, 6);
+        assert_eq!(stitched.height, 4);
+        assert_eq!(stitched.edge(Edge::Right), vec![1, 4, 4, 1]);
+        assert_eq!(
+            stitched.crop(1, 2, 3, 2)?.data,
+            vec![vec![5, 6, 6], vec![2, 3, 3]]
+        );
+        assert!(stitched.crop(5, 0, 2, 1).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stitch_rejects_mismatched_tiles() {
+        let matrix = matrix();
+
+        // The second row is one tile short.
+        assert!(
+            Matrix::stitch(&[vec![matrix.clone(), matrix.clone()], vec![matrix.clone()],]).is_err()
+        );
+
+        // A 3x2 tile beside a 2x3 one.
+        assert!(
+            Matrix::stitch(&[vec![matrix.clone(), matrix.rotate(Rotation::Clockwise90)]]).is_err()
+        );
+
+        // The columns disagree on width.
+        assert!(Matrix::stitch(&[
+            vec![matrix.clone()],
+            vec![from_rows(vec![vec![1, 2], vec![3, 4]])],
+        ])
+        .is_err());
+    }
+}